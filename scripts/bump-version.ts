@@ -62,6 +62,20 @@ function updateCargoToml(newVersion: string): void {
 	console.log(`Updated Cargo.toml to ${newVersion}`);
 }
 
+function updateCargoLock(newVersion: string): void {
+	const filePath = join(rootDir, "src-tauri", "Cargo.lock");
+	let content = readFileSync(filePath, "utf-8");
+
+	// The app's own entry, so `cargo --locked` still accepts the lock file
+	content = content.replace(
+		/^(name = "ai-spotlight-panel"\nversion = )"[^"]+"/m,
+		`$1"${newVersion}"`,
+	);
+
+	writeFileSync(filePath, content);
+	console.log(`Updated Cargo.lock to ${newVersion}`);
+}
+
 function getCurrentVersion(): string {
 	const filePath = join(rootDir, "VERSION");
 	const content = readFileSync(filePath, "utf-8");
@@ -96,6 +110,7 @@ function main(): void {
 	updatePackageJson(newVersion);
 	updateTauriConf(newVersion);
 	updateCargoToml(newVersion);
+	updateCargoLock(newVersion);
 
 	console.log(`\nVersion bumped to ${newVersion}`);
 }
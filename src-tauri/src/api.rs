@@ -0,0 +1,655 @@
+use base64::Engine;
+use futures_util::future::{AbortHandle, Abortable};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::clipboard::{self, ClipboardMode};
+use crate::vault::Vault;
+use crate::{history, knowledge, logging, ollama, pipeline, settings, tray};
+
+// Opt-in local server so editor plugins and scripts can drive the running app. Speaks just enough HTTP/1.1 for
+// curl and fetch: one JSON request per connection, every request authorized with the token from the vault.
+
+const CONFIG_KEY: &str = "localApi";
+// Vault entry holding the bearer token, created the first time the server starts
+pub const TOKEN_NAME: &str = "localApiToken";
+const DEFAULT_ADDRESS: &str = "127.0.0.1:47615";
+const UNIX_PREFIX: &str = "unix:";
+const TOKEN_BYTES: usize = 32;
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+// A client that stops sending mid-request shouldn't hold its connection open forever
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+// The `localApi` setting
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalApiConfig {
+	pub enabled: bool,
+	// A loopback `host:port`, or `unix:<path>` for a Unix socket
+	pub address: String,
+}
+
+impl Default for LocalApiConfig {
+	fn default() -> Self {
+		LocalApiConfig {
+			enabled: false,
+			address: DEFAULT_ADDRESS.to_string(),
+		}
+	}
+}
+
+pub fn configured(app: &AppHandle) -> LocalApiConfig {
+	settings::read(app, CONFIG_KEY).unwrap_or_default()
+}
+
+enum Endpoint {
+	Tcp(SocketAddr),
+	#[cfg(unix)]
+	Unix(std::path::PathBuf),
+}
+
+// Only loopback addresses are accepted: the token guards the API, but it shouldn't be reachable from the network
+fn parse_address(address: &str) -> Result<Endpoint, String> {
+	let address = address.trim();
+	if let Some(path) = address.strip_prefix(UNIX_PREFIX) {
+		if path.is_empty() {
+			return Err("Unix socket path is empty".to_string());
+		}
+		#[cfg(unix)]
+		return Ok(Endpoint::Unix(std::path::PathBuf::from(path)));
+		#[cfg(not(unix))]
+		return Err("Unix sockets are not supported on this platform".to_string());
+	}
+
+	let address = match address.strip_prefix("localhost:") {
+		Some(port) => format!("127.0.0.1:{}", port),
+		None => address.to_string(),
+	};
+	let socket_address = address
+		.parse::<SocketAddr>()
+		.map_err(|e| format!("Invalid local API address \"{}\": {}", address, e))?;
+	if !socket_address.ip().is_loopback() {
+		return Err(format!(
+			"Local API must listen on a loopback address, not {}",
+			socket_address.ip()
+		));
+	}
+	Ok(Endpoint::Tcp(socket_address))
+}
+
+// Check a `localApi` value before it is saved, so the server isn't left failing to start on a bad address
+pub fn validate_config(config: &LocalApiConfig) -> Result<(), String> {
+	parse_address(&config.address).map(|_| ())
+}
+
+struct Running {
+	address: String,
+	abort: AbortHandle,
+}
+
+#[derive(Default)]
+pub struct LocalApiState {
+	running: Mutex<Option<Running>>,
+	// Why the server isn't listening although it's enabled, e.g. the port is taken
+	error: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiStatus {
+	pub enabled: bool,
+	pub address: String,
+	pub listening: bool,
+	pub error: Option<String>,
+}
+
+pub fn status(app: &AppHandle) -> LocalApiStatus {
+	let config = configured(app);
+	let state = app.state::<LocalApiState>();
+	let listening = state.running.lock().expect("local API mutex poisoned").is_some();
+	let error = state.error.lock().expect("local API mutex poisoned").clone();
+	LocalApiStatus {
+		enabled: config.enabled,
+		address: config.address,
+		listening,
+		error,
+	}
+}
+
+// Start, stop or move the server to match the `localApi` setting; called on startup and whenever settings change
+pub fn configure(app: &AppHandle) {
+	let config = configured(app);
+	let state = app.state::<LocalApiState>();
+	let mut running = state.running.lock().expect("local API mutex poisoned");
+	if config.enabled
+		&& running
+			.as_ref()
+			.is_some_and(|running| running.address == config.address)
+	{
+		return;
+	}
+
+	if let Some(previous) = running.take() {
+		previous.abort.abort();
+		remove_socket_file(&previous.address);
+		log::info!("[api] Stopped listening on {}", previous.address);
+	}
+	*state.error.lock().expect("local API mutex poisoned") = None;
+	if !config.enabled {
+		return;
+	}
+
+	if let Err(e) = ensure_token(app) {
+		log::error!("[api] Not starting: {}", e);
+		*state.error.lock().expect("local API mutex poisoned") = Some(e);
+		return;
+	}
+	let endpoint = match parse_address(&config.address) {
+		Ok(endpoint) => endpoint,
+		Err(e) => {
+			log::error!("[api] Not starting: {}", e);
+			*state.error.lock().expect("local API mutex poisoned") = Some(e);
+			return;
+		}
+	};
+
+	let (abort, registration) = AbortHandle::new_pair();
+	*running = Some(Running {
+		address: config.address.clone(),
+		abort,
+	});
+	let handle = app.clone();
+	tauri::async_runtime::spawn(async move {
+		let address = config.address;
+		if let Ok(Err(e)) = Abortable::new(listen(&handle, endpoint, &address), registration).await {
+			log::error!("[api] {}", e);
+			let state = handle.state::<LocalApiState>();
+			let mut running = state.running.lock().expect("local API mutex poisoned");
+			// Leave the state alone if the setting moved the server somewhere else meanwhile
+			if running.as_ref().is_some_and(|running| running.address == address) {
+				*running = None;
+				*state.error.lock().expect("local API mutex poisoned") = Some(e);
+			}
+		}
+	});
+}
+
+async fn listen(app: &AppHandle, endpoint: Endpoint, address: &str) -> Result<(), String> {
+	match endpoint {
+		Endpoint::Tcp(socket_address) => {
+			let listener = tokio::net::TcpListener::bind(socket_address)
+				.await
+				.map_err(|e| format!("Failed to listen on {}: {}", socket_address, e))?;
+			log::info!("[api] Listening on {}", socket_address);
+			loop {
+				let (stream, _) = listener
+					.accept()
+					.await
+					.map_err(|e| format!("Failed to accept connection: {}", e))?;
+				tauri::async_runtime::spawn(serve_connection(app.clone(), stream));
+			}
+		}
+		#[cfg(unix)]
+		Endpoint::Unix(path) => {
+			use std::os::unix::fs::PermissionsExt;
+
+			// A socket left behind by a crash would make the bind fail
+			remove_socket_file(address);
+			let listener = tokio::net::UnixListener::bind(&path)
+				.map_err(|e| format!("Failed to listen on {}: {}", path.display(), e))?;
+			std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+				.map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+			log::info!("[api] Listening on {}", path.display());
+			loop {
+				let (stream, _) = listener
+					.accept()
+					.await
+					.map_err(|e| format!("Failed to accept connection: {}", e))?;
+				tauri::async_runtime::spawn(serve_connection(app.clone(), stream));
+			}
+		}
+	}
+}
+
+fn remove_socket_file(address: &str) {
+	#[cfg(unix)]
+	if let Some(path) = address.trim().strip_prefix(UNIX_PREFIX) {
+		use std::os::unix::fs::FileTypeExt;
+
+		let is_socket = std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket());
+		if is_socket {
+			let _ = std::fs::remove_file(path);
+		}
+	}
+	#[cfg(not(unix))]
+	let _ = address;
+}
+
+// The bearer token clients must send, generated and stored in the vault on first use
+pub fn ensure_token(app: &AppHandle) -> Result<String, String> {
+	let vault = app
+		.try_state::<Vault>()
+		.ok_or_else(|| "Secure storage is unavailable".to_string())?;
+	if let Some(token) = vault.get(TOKEN_NAME).filter(|token| !token.is_empty()) {
+		return Ok(token);
+	}
+	regenerate_token(app)
+}
+
+// Replace the token; clients holding the old one get 401 from the next request on
+pub fn regenerate_token(app: &AppHandle) -> Result<String, String> {
+	let vault = app
+		.try_state::<Vault>()
+		.ok_or_else(|| "Secure storage is unavailable".to_string())?;
+	let mut bytes = [0u8; TOKEN_BYTES];
+	rand::thread_rng().fill_bytes(&mut bytes);
+	let token = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+	vault.set(TOKEN_NAME, &token)?;
+	log::info!("[api] Generated a new token");
+	Ok(token)
+}
+
+// Put the token on the clipboard, so it never has to pass through the webview
+pub fn copy_token(app: &AppHandle) -> Result<(), String> {
+	clipboard::write_text(app, ensure_token(app)?)
+}
+
+struct Request {
+	method: String,
+	path: String,
+	query: Vec<(String, String)>,
+	body: Vec<u8>,
+}
+
+impl Request {
+	fn query_param(&self, name: &str) -> Option<&str> {
+		self.query
+			.iter()
+			.find(|(key, _)| key == name)
+			.map(|(_, value)| value.as_str())
+	}
+
+	fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, Response> {
+		serde_json::from_slice(&self.body)
+			.map_err(|e| Response::error(400, &format!("Invalid request body: {}", e)))
+	}
+}
+
+struct Response {
+	status: u16,
+	body: serde_json::Value,
+}
+
+impl Response {
+	fn ok<T: Serialize>(value: &T) -> Self {
+		match serde_json::to_value(value) {
+			Ok(body) => Response { status: 200, body },
+			Err(e) => Response::error(500, &format!("Failed to serialize response: {}", e)),
+		}
+	}
+
+	fn error(status: u16, message: &str) -> Self {
+		Response {
+			status,
+			body: serde_json::json!({ "error": message }),
+		}
+	}
+}
+
+async fn serve_connection<S: AsyncRead + AsyncWrite + Unpin>(app: AppHandle, mut stream: S) {
+	let token = current_token(&app);
+	let read = read_request(&mut stream, token.as_deref());
+	let response = match tokio::time::timeout(READ_TIMEOUT, read).await {
+		Ok(Ok(request)) => handle_request(&app, request).await,
+		Ok(Err(response)) => response,
+		Err(_) => Response::error(408, "Timed out reading the request"),
+	};
+	if let Err(e) = write_response(&mut stream, &response).await {
+		log::warn!("[api] Failed to send response: {}", e);
+	}
+}
+
+// Parse a request, checking the token before reading a body so unauthorized clients can't make the app buffer one
+async fn read_request<S: AsyncRead + Unpin>(
+	stream: &mut S,
+	token: Option<&str>,
+) -> Result<Request, Response> {
+	let mut buffer = Vec::new();
+	let mut chunk = [0u8; 4096];
+	let head_end = loop {
+		if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+			break position;
+		}
+		if buffer.len() > MAX_HEAD_BYTES {
+			return Err(Response::error(431, "Request headers too large"));
+		}
+		let read = stream
+			.read(&mut chunk)
+			.await
+			.map_err(|e| Response::error(400, &format!("Failed to read request: {}", e)))?;
+		if read == 0 {
+			return Err(Response::error(400, "Incomplete request"));
+		}
+		buffer.extend_from_slice(&chunk[..read]);
+	};
+
+	let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+	let mut lines = head.split("\r\n");
+	let mut request_line = lines.next().unwrap_or_default().split(' ');
+	let method = request_line.next().unwrap_or_default().to_string();
+	let target = request_line.next().unwrap_or_default();
+	let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+	let mut content_length = 0;
+	let mut authorization = None;
+	for line in lines {
+		let Some((name, value)) = line.split_once(':') else {
+			continue;
+		};
+		match name.trim().to_ascii_lowercase().as_str() {
+			"content-length" => {
+				content_length = value
+					.trim()
+					.parse::<usize>()
+					.map_err(|_| Response::error(400, "Invalid Content-Length"))?;
+			}
+			"authorization" => authorization = Some(value.trim().to_string()),
+			_ => {}
+		}
+	}
+	if !authorized(token, authorization.as_deref()) {
+		log::warn!("[api] Rejected unauthorized {} {}", method, path);
+		return Err(Response::error(401, "Missing or invalid token"));
+	}
+	if content_length > MAX_BODY_BYTES {
+		return Err(Response::error(413, "Request body too large"));
+	}
+
+	let mut body = buffer.split_off(head_end + 4);
+	while body.len() < content_length {
+		let read = stream
+			.read(&mut chunk)
+			.await
+			.map_err(|e| Response::error(400, &format!("Failed to read request: {}", e)))?;
+		if read == 0 {
+			return Err(Response::error(400, "Incomplete request body"));
+		}
+		body.extend_from_slice(&chunk[..read]);
+	}
+	body.truncate(content_length);
+
+	Ok(Request {
+		method,
+		path: path.to_string(),
+		query: parse_query(query),
+		body,
+	})
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+	query
+		.split('&')
+		.filter(|pair| !pair.is_empty())
+		.map(|pair| {
+			let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+			let decode = |part: &str| {
+				urlencoding::decode(&part.replace('+', " "))
+					.map(|decoded| decoded.into_owned())
+					.unwrap_or_else(|_| part.to_string())
+			};
+			(decode(key), decode(value))
+		})
+		.collect()
+}
+
+async fn write_response<S: AsyncWrite + Unpin>(
+	stream: &mut S,
+	response: &Response,
+) -> std::io::Result<()> {
+	let body = response.body.to_string();
+	let head = format!(
+		"HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+		response.status,
+		reason(response.status),
+		body.len()
+	);
+	stream.write_all(head.as_bytes()).await?;
+	stream.write_all(body.as_bytes()).await?;
+	stream.shutdown().await
+}
+
+fn reason(status: u16) -> &'static str {
+	match status {
+		200 => "OK",
+		400 => "Bad Request",
+		401 => "Unauthorized",
+		404 => "Not Found",
+		405 => "Method Not Allowed",
+		408 => "Request Timeout",
+		413 => "Payload Too Large",
+		431 => "Request Header Fields Too Large",
+		502 => "Bad Gateway",
+		_ => "Internal Server Error",
+	}
+}
+
+// The token requests must carry; without one, e.g. when the vault is unavailable, every request is rejected
+fn current_token(app: &AppHandle) -> Option<String> {
+	app.try_state::<Vault>()
+		.and_then(|vault| vault.get(TOKEN_NAME))
+		.filter(|token| !token.is_empty())
+}
+
+fn authorized(token: Option<&str>, authorization: Option<&str>) -> bool {
+	let Some(token) = token.filter(|token| !token.is_empty()) else {
+		return false;
+	};
+	let Some(given) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+		return false;
+	};
+	// Compare without an early exit so response timing doesn't leak how much of the token matched
+	given.len() == token.len()
+		&& given
+			.bytes()
+			.zip(token.bytes())
+			.fold(0u8, |diff, (a, b)| diff | (a ^ b))
+			== 0
+}
+
+#[derive(Debug, Deserialize)]
+struct AskBody {
+	text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslateBody {
+	text: String,
+	// Target language code; without it the panel's rules apply (to English, or English to the second language)
+	to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShowBody {
+	text: String,
+	#[serde(default)]
+	mode: ClipboardMode,
+}
+
+async fn handle_request(app: &AppHandle, request: Request) -> Response {
+	log::info!(
+		"[api] {} {} body={}",
+		request.method,
+		request.path,
+		logging::text(&String::from_utf8_lossy(&request.body))
+	);
+
+	let result = match (request.method.as_str(), request.path.as_str()) {
+		("POST", "/ask") => ask(app, &request).await,
+		("POST", "/translate") => translate(app, &request).await,
+		("POST", "/show") => show(app, &request),
+		("GET", "/history") => list_history(app, &request),
+		(_, "/ask" | "/translate" | "/show" | "/history") => {
+			Err(Response::error(405, "Method not allowed"))
+		}
+		_ => Err(Response::error(404, "Not found")),
+	};
+	result.unwrap_or_else(|response| response)
+}
+
+fn non_empty(text: &str) -> Result<(), Response> {
+	if text.trim().is_empty() {
+		return Err(Response::error(400, "Empty text"));
+	}
+	Ok(())
+}
+
+// Same pipeline and bookkeeping as the `quick_answer` command, without taking over the panel's cancel button
+async fn ask(app: &AppHandle, request: &Request) -> Result<Response, Response> {
+	let body: AskBody = request.json()?;
+	non_empty(&body.text)?;
+	let knowledge_base = app.state::<knowledge::KnowledgeBase>();
+	let model_profiles = app.state::<ollama::ModelProfiles>();
+	let answer = pipeline::quick_answer(app, &knowledge_base, &model_profiles, body.text.clone())
+		.await
+		.map_err(|e| Response::error(502, &e))?;
+	app.state::<clipboard::LastResults>()
+		.set(clipboard::ResultKind::Answer, &answer.text);
+	history::record_quick_answer(app, &body.text, &answer.text, &answer.model);
+	tray::refresh(app);
+	Ok(Response::ok(&answer))
+}
+
+async fn translate(app: &AppHandle, request: &Request) -> Result<Response, Response> {
+	let body: TranslateBody = request.json()?;
+	non_empty(&body.text)?;
+	let result = match body.to.as_deref().map(str::trim).filter(|to| !to.is_empty()) {
+		Some(language) => pipeline::translate_to(app, &body.text, language).await,
+		None => pipeline::translate(app, &body.text).await,
+	}
+	.map_err(|e| Response::error(502, &e))?;
+	app.state::<clipboard::LastResults>()
+		.set(clipboard::ResultKind::Translation, &result.text);
+	Ok(Response::ok(&result))
+}
+
+fn show(app: &AppHandle, request: &Request) -> Result<Response, Response> {
+	let body: ShowBody = request.json()?;
+	non_empty(&body.text)?;
+	// The panel has to be shown from the main thread, like the hotkey and tray handlers do
+	let handle = app.clone();
+	app.run_on_main_thread(move || {
+		crate::show_spotlight_with_query(&handle, body.text, body.mode.as_str(), true)
+	})
+	.map_err(|e| Response::error(500, &format!("Failed to show the panel: {}", e)))?;
+	Ok(Response::ok(&serde_json::json!({ "shown": true })))
+}
+
+fn list_history(app: &AppHandle, request: &Request) -> Result<Response, Response> {
+	let limit = match request.query_param("limit") {
+		Some(limit) => limit
+			.parse::<usize>()
+			.map_err(|_| Response::error(400, "Invalid limit"))?,
+		None => DEFAULT_HISTORY_LIMIT,
+	};
+	Ok(Response::ok(&app.state::<history::History>().recent(limit)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TOKEN: &str = "secret-token";
+
+	fn read(raw: &str, token: Option<&str>) -> Result<Request, Response> {
+		tauri::async_runtime::block_on(read_request(&mut raw.as_bytes(), token))
+	}
+
+	#[test]
+	fn parses_an_authorized_request() {
+		let raw = "POST /translate?limit=5&q=a+b%21 HTTP/1.1\r\nHost: localhost\r\n\
+			Authorization: Bearer secret-token\r\nContent-Length: 17\r\n\r\n{\"text\":\"hello\"}\n";
+		let request = read(raw, Some(TOKEN)).ok().expect("request should parse");
+		assert_eq!(request.method, "POST");
+		assert_eq!(request.path, "/translate");
+		assert_eq!(request.query_param("limit"), Some("5"));
+		assert_eq!(request.query_param("q"), Some("a b!"));
+		assert_eq!(request.body, b"{\"text\":\"hello\"}\n");
+	}
+
+	#[test]
+	fn header_names_are_case_insensitive() {
+		let raw = "GET /history HTTP/1.1\r\nauthorization: Bearer secret-token\r\n\r\n";
+		assert!(read(raw, Some(TOKEN)).is_ok());
+	}
+
+	#[test]
+	fn rejects_missing_or_wrong_tokens() {
+		for raw in [
+			"GET /history HTTP/1.1\r\n\r\n",
+			"GET /history HTTP/1.1\r\nAuthorization: Bearer wrong-token!\r\n\r\n",
+			"GET /history HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n",
+			"GET /history HTTP/1.1\r\nAuthorization: secret-token\r\n\r\n",
+		] {
+			assert_eq!(read(raw, Some(TOKEN)).err().map(|r| r.status), Some(401), "{:?}", raw);
+		}
+	}
+
+	#[test]
+	fn rejects_every_request_without_a_token() {
+		let raw = "GET /history HTTP/1.1\r\nAuthorization: Bearer \r\n\r\n";
+		assert_eq!(read(raw, None).err().map(|r| r.status), Some(401));
+		assert_eq!(read(raw, Some("")).err().map(|r| r.status), Some(401));
+		assert!(!authorized(None, Some("Bearer ")));
+		assert!(!authorized(Some(""), Some("Bearer ")));
+	}
+
+	#[test]
+	fn checks_the_token_before_the_body_size() {
+		let raw = format!("POST /ask HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_BYTES + 1);
+		assert_eq!(read(&raw, Some(TOKEN)).err().map(|r| r.status), Some(401));
+
+		let raw = format!(
+			"POST /ask HTTP/1.1\r\nAuthorization: Bearer secret-token\r\nContent-Length: {}\r\n\r\n",
+			MAX_BODY_BYTES + 1
+		);
+		assert_eq!(read(&raw, Some(TOKEN)).err().map(|r| r.status), Some(413));
+	}
+
+	#[test]
+	fn rejects_malformed_requests() {
+		let cases = [
+			("GET / HTTP/1.1\r\nAuthorization: Bearer secret-token\r\n", 400),
+			(
+				"POST /ask HTTP/1.1\r\nAuthorization: Bearer secret-token\r\nContent-Length: x\r\n\r\n",
+				400,
+			),
+			(
+				"POST /ask HTTP/1.1\r\nAuthorization: Bearer secret-token\r\nContent-Length: 10\r\n\r\nshort",
+				400,
+			),
+		];
+		for (raw, status) in cases {
+			assert_eq!(read(raw, Some(TOKEN)).err().map(|r| r.status), Some(status), "{:?}", raw);
+		}
+
+		let raw = format!("GET / HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(2 * MAX_HEAD_BYTES));
+		assert_eq!(read(&raw, Some(TOKEN)).err().map(|r| r.status), Some(431));
+	}
+
+	#[test]
+	fn accepts_only_loopback_addresses() {
+		assert!(matches!(parse_address("127.0.0.1:47615"), Ok(Endpoint::Tcp(_))));
+		assert!(matches!(parse_address("localhost:8080"), Ok(Endpoint::Tcp(_))));
+		assert!(matches!(parse_address("[::1]:8080"), Ok(Endpoint::Tcp(_))));
+		assert!(parse_address("0.0.0.0:47615").is_err());
+		assert!(parse_address("192.168.1.2:47615").is_err());
+		assert!(parse_address("not an address").is_err());
+		assert!(parse_address("unix:").is_err());
+	}
+}
@@ -0,0 +1,4 @@
+// Headless entry point: `spotlight-cli ask "..."`, `spotlight-cli translate --to de "..."`
+fn main() -> std::process::ExitCode {
+	ai_spotlight_panel_lib::cli::run()
+}
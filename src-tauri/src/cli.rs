@@ -0,0 +1,187 @@
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::settings::{SettingsFile, SettingsSource};
+use crate::{api, embeddings, history, knowledge, logging, ollama, pipeline};
+
+// Same as `identifier` in tauri.conf.json, checked by a test: the app keeps settings, the vault and history in the
+// data directory named after it
+const APP_IDENTIFIER: &str = "com.konstantin.ai-spotlight-panel";
+
+const USAGE: &str = "Usage:
+  spotlight-cli ask [TEXT...]
+  spotlight-cli translate [--to LANGUAGE] [TEXT...]
+  spotlight-cli api-token
+
+TEXT is read from stdin when omitted. Settings, the active profile and history are shared with the app.";
+
+enum Command {
+	Ask(String),
+	Translate { text: String, to: Option<String> },
+	// Print the local API token, for scripts that call the running app
+	ApiToken,
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+	let (command, rest) = args
+		.split_first()
+		.ok_or_else(|| "Missing command".to_string())?;
+	match command.as_str() {
+		"ask" => Ok(Command::Ask(read_text(rest)?)),
+		"translate" => match rest {
+			[flag, language, text @ ..] if flag == "--to" => Ok(Command::Translate {
+				text: read_text(text)?,
+				to: Some(language.clone()),
+			}),
+			[flag] if flag == "--to" => Err("--to needs a language code".to_string()),
+			text => Ok(Command::Translate {
+				text: read_text(text)?,
+				to: None,
+			}),
+		},
+		"api-token" => Ok(Command::ApiToken),
+		"help" | "--help" | "-h" => Err(String::new()),
+		other => Err(format!("Unknown command \"{}\"", other)),
+	}
+}
+
+// The remaining arguments joined with spaces, or stdin when there are none
+fn read_text(args: &[String]) -> Result<String, String> {
+	// A mistyped option shouldn't be sent to the model as the text
+	if let Some(option) = args.first().filter(|arg| arg.starts_with("--")) {
+		return Err(format!("Unknown option \"{}\"", option));
+	}
+	let text = if args.is_empty() {
+		let mut input = String::new();
+		std::io::stdin()
+			.read_to_string(&mut input)
+			.map_err(|e| format!("Failed to read stdin: {}", e))?;
+		input
+	} else {
+		args.join(" ")
+	};
+	if text.trim().is_empty() {
+		return Err("No text given".to_string());
+	}
+	Ok(text.trim().to_string())
+}
+
+async fn execute(source: &SettingsFile, data_dir: &Path, command: Command) -> Result<String, String> {
+	match command {
+		Command::Ask(text) => {
+			let embedding_model = embeddings::configured_model(source).unwrap_or_default();
+			let knowledge_base = knowledge::KnowledgeBase::load(data_dir, &embedding_model);
+			let model_profiles = ollama::ModelProfiles::default();
+			let answer =
+				pipeline::quick_answer(source, &knowledge_base, &model_profiles, text.clone()).await?;
+
+			// Recorded in the app's history file, so CLI answers show up in its search and tray too
+			let history = history::History::load(data_dir, &embedding_model);
+			match history.record(&text, &answer.text, &answer.model) {
+				Ok(_) if !embedding_model.is_empty() => {
+					if let Err(e) = history.embed_missing(&embedding_model).await {
+						eprintln!("warning: failed to embed history: {}", e);
+					}
+				}
+				Ok(_) => {}
+				Err(e) => eprintln!("warning: {}", e),
+			}
+			Ok(answer.text)
+		}
+		Command::Translate { text, to: Some(language) } => {
+			Ok(pipeline::translate_to(source, &text, &language).await?.text)
+		}
+		Command::Translate { text, to: None } => Ok(pipeline::translate(source, &text).await?.text),
+		Command::ApiToken => source
+			.secret(api::TOKEN_NAME)
+			.ok_or_else(|| "No local API token yet; enable localApi in the app first".to_string()),
+	}
+}
+
+// Entry point of the `spotlight-cli` binary
+pub fn run() -> ExitCode {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	let command = match parse_args(&args) {
+		Ok(command) => command,
+		Err(e) if e.is_empty() => {
+			println!("{}", USAGE);
+			return ExitCode::SUCCESS;
+		}
+		Err(e) => {
+			eprintln!("error: {}\n\n{}", e, USAGE);
+			return ExitCode::from(2);
+		}
+	};
+
+	let Some(data_dir) = dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER)) else {
+		eprintln!("error: cannot determine the data directory");
+		return ExitCode::FAILURE;
+	};
+	let source = SettingsFile::load(&data_dir);
+	logging::configure(&source);
+	ollama::configure(&source);
+
+	match tauri::async_runtime::block_on(execute(&source, &data_dir, command)) {
+		Ok(output) => {
+			println!("{}", output);
+			ExitCode::SUCCESS
+		}
+		Err(e) => {
+			eprintln!("error: {}", e);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(args: &[&str]) -> Result<Command, String> {
+		parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+	}
+
+	#[test]
+	fn parses_ask() {
+		assert!(matches!(
+			parse(&["ask", "what", "is", "Rust"]),
+			Ok(Command::Ask(text)) if text == "what is Rust"
+		));
+	}
+
+	#[test]
+	fn parses_translate() {
+		assert!(matches!(
+			parse(&["translate", "--to", "de", "Good", "morning"]),
+			Ok(Command::Translate { text, to: Some(to) }) if text == "Good morning" && to == "de"
+		));
+		assert!(matches!(
+			parse(&["translate", "Guten", "Morgen"]),
+			Ok(Command::Translate { text, to: None }) if text == "Guten Morgen"
+		));
+		assert!(parse(&["translate", "--to"]).is_err());
+	}
+
+	#[test]
+	fn parses_api_token() {
+		assert!(matches!(parse(&["api-token"]), Ok(Command::ApiToken)));
+	}
+
+	#[test]
+	fn rejects_unknown_commands_and_options() {
+		assert!(parse(&[]).is_err());
+		assert!(parse(&["summarize", "text"]).is_err());
+		assert!(parse(&["ask", "--model", "llama3", "hi"]).is_err());
+		assert!(parse(&["translate", "--from", "fr", "Bonjour"]).is_err());
+		assert!(parse(&["translate", "--to", "de", "--verbose", "hi"]).is_err());
+		assert_eq!(parse(&["--help"]).err().as_deref(), Some(""));
+	}
+
+	#[test]
+	fn identifier_matches_the_app_config() {
+		let config: serde_json::Value =
+			serde_json::from_str(include_str!("../tauri.conf.json")).expect("tauri.conf.json should parse");
+		assert_eq!(config["identifier"], APP_IDENTIFIER);
+	}
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::settings;
+
+// What the "Ask about clipboard" action runs on the copied text
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardMode {
+	#[default]
+	Ask,
+	Translate,
+}
+
+impl ClipboardMode {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			ClipboardMode::Ask => "ask",
+			ClipboardMode::Translate => "translate",
+		}
+	}
+}
+
+pub fn configured_mode(app: &AppHandle) -> ClipboardMode {
+	settings::read(app, "clipboardActionMode").unwrap_or_default()
+}
+
+// Read the clipboard and open the panel with its text, running the chosen mode straight away
+pub fn ask_about_clipboard(app: &AppHandle, mode: Option<ClipboardMode>) -> Result<(), String> {
+	let text = app
+		.clipboard()
+		.read_text()
+		.map_err(|e| format!("Failed to read clipboard: {}", e))?;
+	if text.trim().is_empty() {
+		return Err("Clipboard has no text".to_string());
+	}
+
+	let mode = mode.unwrap_or_else(|| configured_mode(app));
+	log::info!(
+		"[clipboard] Opening panel with clipboard text mode={:?}, text_len={}",
+		mode,
+		text.len()
+	);
+	crate::show_spotlight_with_query(app, text, mode.as_str(), true);
+	Ok(())
+}
+
+// Entry point for the hotkey and tray item, reporting failures with a toast since there is no caller to return to
+pub fn trigger(app: &AppHandle) {
+	if let Err(e) = ask_about_clipboard(app, None) {
+		log::warn!("[clipboard] {}", e);
+		tauri::async_runtime::spawn(crate::show_toast(app.clone(), e));
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResultKind {
+	Answer,
+	Translation,
+}
+
+impl ResultKind {
+	pub fn copied_message(self) -> &'static str {
+		match self {
+			ResultKind::Answer => "Answer copied",
+			ResultKind::Translation => "Translation copied",
+		}
+	}
+}
+
+// Latest quick answer and translation, so results can leave the panel without going through the UI
+#[derive(Default)]
+pub struct LastResults {
+	inner: Mutex<LastResultsInner>,
+}
+
+#[derive(Default)]
+struct LastResultsInner {
+	answer: Option<String>,
+	translation: Option<String>,
+	latest: Option<ResultKind>,
+}
+
+impl LastResults {
+	pub fn set(&self, kind: ResultKind, text: &str) {
+		let mut inner = self.inner.lock().expect("last results mutex poisoned");
+		match kind {
+			ResultKind::Answer => inner.answer = Some(text.to_string()),
+			ResultKind::Translation => inner.translation = Some(text.to_string()),
+		}
+		inner.latest = Some(kind);
+	}
+
+	// The result of the given kind, or whichever finished last
+	pub fn get(&self, kind: Option<ResultKind>) -> Option<(ResultKind, String)> {
+		let inner = self.inner.lock().expect("last results mutex poisoned");
+		let kind = kind.or(inner.latest)?;
+		let text = match kind {
+			ResultKind::Answer => inner.answer.clone(),
+			ResultKind::Translation => inner.translation.clone(),
+		}?;
+		Some((kind, text))
+	}
+}
+
+pub fn write_text(app: &AppHandle, text: String) -> Result<(), String> {
+	app.clipboard()
+		.write_text(text)
+		.map_err(|e| format!("Failed to write clipboard: {}", e))
+}
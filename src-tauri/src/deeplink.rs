@@ -0,0 +1,197 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Url};
+
+// `ai-spotlight://ask?q=<text>&mode=<ask|translate|all>&run=1`, registered as `plugins.deep-link` in
+// tauri.conf.json. Without `run` the text is only filled in, so a link on a web page can't send anything to the
+// model until the user presses Enter.
+
+pub const SCHEME: &str = "ai-spotlight";
+// Links come from anywhere, so their text is capped like a long paste rather than trusted
+const MAX_QUERY_CHARS: usize = 20_000;
+
+// Links that arrived before the panel could receive them, e.g. the one the app was launched with
+#[derive(Default)]
+pub struct PendingLinks(Mutex<PendingState>);
+
+#[derive(Default)]
+struct PendingState {
+	// Set once the panel listens for `spotlight://query`; links are opened right away from then on
+	ready: bool,
+	urls: Vec<Url>,
+}
+
+impl PendingLinks {
+	// Queue links until the panel is ready; a cold start can report the same link both ways, so it's kept once
+	pub fn push(&self, urls: Vec<Url>) {
+		let mut pending = self.0.lock().expect("pending links mutex poisoned");
+		for url in urls {
+			if !pending.urls.contains(&url) {
+				pending.urls.push(url);
+			}
+		}
+	}
+
+	// Queue the link instead of opening it when the panel isn't ready yet
+	fn defer(&self, url: &Url) -> bool {
+		let mut pending = self.0.lock().expect("pending links mutex poisoned");
+		if pending.ready {
+			return false;
+		}
+		if !pending.urls.contains(url) {
+			pending.urls.push(url.clone());
+		}
+		true
+	}
+}
+
+// Called by the panel once it listens for `spotlight://query`
+pub fn open_pending(app: &AppHandle) {
+	let urls = {
+		let state = app.state::<PendingLinks>();
+		let mut pending = state.0.lock().expect("pending links mutex poisoned");
+		pending.ready = true;
+		std::mem::take(&mut pending.urls)
+	};
+	for url in urls {
+		open(app, &url);
+	}
+}
+
+#[derive(Debug)]
+struct DeepLink {
+	text: String,
+	mode: &'static str,
+	run: bool,
+}
+
+fn parse(url: &Url) -> Result<DeepLink, String> {
+	if url.scheme() != SCHEME {
+		return Err(format!("Unsupported link scheme \"{}\"", url.scheme()));
+	}
+	// `ai-spotlight://ask?...` has the action as host, `ai-spotlight:ask?...` as path
+	let action = url
+		.host_str()
+		.unwrap_or_else(|| url.path().trim_matches('/'));
+	if action != "ask" {
+		return Err(format!("Unknown link action \"{}\"", action));
+	}
+
+	let mut text = None;
+	let mut mode = "ask";
+	let mut run = false;
+	for (key, value) in url.query_pairs() {
+		match key.as_ref() {
+			"q" => text = Some(value.into_owned()),
+			"mode" => {
+				mode = match value.as_ref() {
+					"ask" => "ask",
+					"translate" => "translate",
+					"all" => "all",
+					other => return Err(format!("Unknown link mode \"{}\"", other)),
+				}
+			}
+			"run" => run = matches!(value.as_ref(), "1" | "true" | "yes"),
+			_ => {}
+		}
+	}
+
+	let text = text.unwrap_or_default();
+	if text.trim().is_empty() {
+		return Err("Link has no query".to_string());
+	}
+	if text.chars().count() > MAX_QUERY_CHARS {
+		return Err(format!(
+			"Link query is longer than {} characters",
+			MAX_QUERY_CHARS
+		));
+	}
+	Ok(DeepLink { text, mode, run })
+}
+
+// Open the panel for a link, reporting bad links with a toast since the caller is another app
+pub fn open(app: &AppHandle, url: &Url) {
+	// On macOS a cold-start link comes through the plugin's handler before the panel has loaded
+	if app.state::<PendingLinks>().defer(url) {
+		log::info!("[deeplink] Panel not ready, queued the link");
+		return;
+	}
+	let link = match parse(url) {
+		Ok(link) => link,
+		Err(e) => {
+			log::warn!("[deeplink] {}", e);
+			tauri::async_runtime::spawn(crate::show_toast(app.clone(), e));
+			return;
+		}
+	};
+	log::info!(
+		"[deeplink] Opening panel mode={}, run={}, text_len={}",
+		link.mode,
+		link.run,
+		link.text.len()
+	);
+
+	// Links can arrive on the plugin's thread; the panel is shown from the main thread like the hotkey handler does
+	let handle = app.clone();
+	let result = app.run_on_main_thread(move || {
+		if link.run {
+			crate::show_spotlight_with_query(&handle, link.text, link.mode, true);
+		} else {
+			crate::show_spotlight_with_draft(&handle, link.text, link.mode);
+		}
+	});
+	if let Err(e) = result {
+		log::error!("[deeplink] Failed to show the panel: {}", e);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse_str(link: &str) -> Result<DeepLink, String> {
+		parse(&Url::parse(link).expect("test link should be a URL"))
+	}
+
+	#[test]
+	fn parses_a_link_with_defaults() {
+		let link = parse_str("ai-spotlight://ask?q=Guten%20Morgen").unwrap();
+		assert_eq!(link.text, "Guten Morgen");
+		assert_eq!(link.mode, "ask");
+		assert!(!link.run);
+	}
+
+	#[test]
+	fn parses_mode_and_run() {
+		let link = parse_str("ai-spotlight://ask?q=hi&mode=translate&run=1").unwrap();
+		assert_eq!(link.mode, "translate");
+		assert!(link.run);
+		assert!(parse_str("ai-spotlight://ask?q=hi&mode=all&run=true").unwrap().run);
+		assert!(!parse_str("ai-spotlight://ask?q=hi&run=0").unwrap().run);
+	}
+
+	#[test]
+	fn accepts_the_action_as_path() {
+		assert_eq!(parse_str("ai-spotlight:ask?q=hi").unwrap().text, "hi");
+	}
+
+	#[test]
+	fn rejects_bad_links() {
+		for link in [
+			"https://ask?q=hi",
+			"ai-spotlight://run?q=hi",
+			"ai-spotlight://ask?q=hi&mode=shell",
+			"ai-spotlight://ask",
+			"ai-spotlight://ask?q=%20%20",
+		] {
+			assert!(parse_str(link).is_err(), "{} should be rejected", link);
+		}
+	}
+
+	#[test]
+	fn caps_the_query_length() {
+		let at_limit = format!("ai-spotlight://ask?q={}", "a".repeat(MAX_QUERY_CHARS));
+		assert!(parse_str(&at_limit).is_ok());
+		let over_limit = format!("ai-spotlight://ask?q={}", "a".repeat(MAX_QUERY_CHARS + 1));
+		assert!(parse_str(&over_limit).is_err());
+	}
+}
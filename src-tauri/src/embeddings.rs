@@ -0,0 +1,420 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use crate::ollama;
+use crate::settings::{self, SettingsSource};
+
+const INDEX_MAGIC: &[u8; 4] = b"ASVI";
+const INDEX_VERSION: u32 = 1;
+const EMBED_BATCH_SIZE: usize = 16;
+
+// Embedding model shared by the local docs index and history search, `None` when disabled
+pub fn configured_model(source: &impl SettingsSource) -> Option<String> {
+	settings::read::<String>(source, "embeddingModel")
+		.map(|model| model.trim().to_string())
+		.filter(|model| !model.is_empty())
+}
+
+#[derive(Debug, Serialize)]
+struct EmbedRequest<'a> {
+	model: &'a str,
+	input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+	embeddings: Vec<Vec<f32>>,
+}
+
+// Embed texts through Ollama's /api/embed, batching to keep request bodies small
+pub async fn embed_texts(
+	client: &reqwest::Client,
+	model: &str,
+	texts: &[String],
+) -> Result<Vec<Vec<f32>>, String> {
+	let mut embeddings = Vec::with_capacity(texts.len());
+
+	for batch in texts.chunks(EMBED_BATCH_SIZE) {
+		let json_body = serde_json::to_string(&EmbedRequest { model, input: batch })
+			.map_err(|e| format!("Failed to serialize embed request: {}", e))?;
+
+		let response = client
+			.post(format!("{}/api/embed", ollama::base_url()))
+			.header("Content-Type", "application/json")
+			.body(json_body)
+			.send()
+			.await
+			.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
+
+		if !response.status().is_success() {
+			return Err(format!("Ollama API error: {}", response.status()));
+		}
+
+		let body_bytes = response
+			.bytes()
+			.await
+			.map_err(|e| format!("Failed to read embed response: {}", e))?;
+		let embed_response: EmbedResponse = serde_json::from_slice(&body_bytes)
+			.map_err(|e| format!("Failed to parse embed response: {}", e))?;
+
+		if embed_response.embeddings.len() != batch.len() {
+			return Err("Embedding count does not match input count".to_string());
+		}
+		embeddings.extend(embed_response.embeddings);
+	}
+
+	Ok(embeddings)
+}
+
+pub async fn embed_one(
+	client: &reqwest::Client,
+	model: &str,
+	text: &str,
+) -> Result<Vec<f32>, String> {
+	embed_texts(client, model, &[text.to_string()])
+		.await?
+		.pop()
+		.ok_or_else(|| "No embedding returned".to_string())
+}
+
+// Flat, brute-force vector index. Vectors are normalized on insert so scoring is a
+// plain dot product, which stays fast enough on CPU for tens of thousands of entries.
+//
+// On-disk layout (little endian):
+//   "ASVI" | version u32 | model_len u32 | model | dims u32 | count u32
+//   then per entry: id_len u32 | id | dims * f32
+#[derive(Debug, Default)]
+pub struct VectorIndex {
+	model: String,
+	dims: usize,
+	ids: Vec<String>,
+	vectors: Vec<f32>,
+	// Position of each id in `ids`, so inserts and lookups don't scan the whole index
+	positions: HashMap<String, usize>,
+}
+
+impl VectorIndex {
+	pub fn new(model: &str) -> Self {
+		VectorIndex {
+			model: model.to_string(),
+			..Default::default()
+		}
+	}
+
+	// Load an index from disk, starting empty if the file is missing, corrupt, or built with a different model
+	pub fn load(path: &Path, model: &str) -> Self {
+		let read = std::fs::File::open(path).and_then(|file| {
+			let len = file.metadata()?.len();
+			Self::read_from(&mut io::BufReader::new(file), len)
+		});
+		match read {
+			Ok(index) if index.model == model => index,
+			Ok(_) => {
+				log::info!(
+					"[embeddings] Discarding {} built with a different model",
+					path.display()
+				);
+				Self::new(model)
+			}
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Self::new(model),
+			Err(e) => {
+				log::warn!("[embeddings] Failed to load {}: {}", path.display(), e);
+				Self::new(model)
+			}
+		}
+	}
+
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)
+				.map_err(|e| format!("Failed to create index directory: {}", e))?;
+		}
+		let mut buffer = Vec::with_capacity(self.vectors.len() * 4 + self.ids.len() * 32);
+		self.write_to(&mut buffer)
+			.map_err(|e| format!("Failed to encode vector index: {}", e))?;
+		std::fs::write(path, buffer).map_err(|e| format!("Failed to write vector index: {}", e))
+	}
+
+	pub fn model(&self) -> &str {
+		&self.model
+	}
+
+	pub fn len(&self) -> usize {
+		self.ids.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.ids.is_empty()
+	}
+
+	pub fn ids(&self) -> impl Iterator<Item = &str> {
+		self.ids.iter().map(String::as_str)
+	}
+
+	pub fn contains(&self, id: &str) -> bool {
+		self.positions.contains_key(id)
+	}
+
+	// Insert or replace a vector. The first insert fixes the dimensionality of the index.
+	pub fn upsert(&mut self, id: &str, vector: &[f32]) -> Result<(), String> {
+		if vector.is_empty() {
+			return Err("Empty embedding".to_string());
+		}
+		if self.dims == 0 {
+			self.dims = vector.len();
+		} else if vector.len() != self.dims {
+			return Err(format!(
+				"Embedding has {} dimensions, index expects {}",
+				vector.len(),
+				self.dims
+			));
+		}
+
+		let normalized = normalize(vector);
+		match self.positions.get(id) {
+			Some(&position) => {
+				let start = position * self.dims;
+				self.vectors[start..start + self.dims].copy_from_slice(&normalized);
+			}
+			None => {
+				self.positions.insert(id.to_string(), self.ids.len());
+				self.ids.push(id.to_string());
+				self.vectors.extend_from_slice(&normalized);
+			}
+		}
+		Ok(())
+	}
+
+	// Drop every entry whose id fails the predicate
+	pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+		let mut ids = Vec::with_capacity(self.ids.len());
+		let mut vectors = Vec::with_capacity(self.vectors.len());
+		for (i, id) in self.ids.iter().enumerate() {
+			if keep(id) {
+				ids.push(id.clone());
+				vectors.extend_from_slice(&self.vectors[i * self.dims..(i + 1) * self.dims]);
+			}
+		}
+		self.ids = ids;
+		self.vectors = vectors;
+		self.positions = positions_of(&self.ids);
+		if self.ids.is_empty() {
+			self.dims = 0;
+		}
+	}
+
+	// Return up to `limit` entries ordered by cosine similarity to the query
+	pub fn search(&self, query: &[f32], limit: usize) -> Vec<(String, f32)> {
+		if self.dims == 0 || query.len() != self.dims {
+			return Vec::new();
+		}
+
+		let query = normalize(query);
+		let mut scored: Vec<(f32, usize)> = self
+			.vectors
+			.chunks_exact(self.dims)
+			.enumerate()
+			.map(|(i, vector)| (dot(&query, vector), i))
+			.collect();
+		scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+		scored
+			.into_iter()
+			.take(limit)
+			.map(|(score, i)| (self.ids[i].clone(), score))
+			.collect()
+	}
+
+	fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+		out.write_all(INDEX_MAGIC)?;
+		out.write_all(&INDEX_VERSION.to_le_bytes())?;
+		write_bytes(out, self.model.as_bytes())?;
+		out.write_all(&(self.dims as u32).to_le_bytes())?;
+		out.write_all(&(self.ids.len() as u32).to_le_bytes())?;
+		for (i, id) in self.ids.iter().enumerate() {
+			write_bytes(out, id.as_bytes())?;
+			for value in &self.vectors[i * self.dims..(i + 1) * self.dims] {
+				out.write_all(&value.to_le_bytes())?;
+			}
+		}
+		Ok(())
+	}
+
+	// `file_len` bounds the sizes in the header, so a corrupt file fails to load instead of allocating wildly
+	fn read_from(input: &mut impl Read, file_len: u64) -> io::Result<Self> {
+		let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+		let mut magic = [0u8; 4];
+		input.read_exact(&mut magic)?;
+		if &magic != INDEX_MAGIC {
+			return Err(invalid("not a vector index"));
+		}
+		if read_u32(input)? != INDEX_VERSION {
+			return Err(invalid("unsupported vector index version"));
+		}
+		let model = String::from_utf8(read_bytes(input, file_len)?)
+			.map_err(|_| invalid("invalid model name"))?;
+		let dims = read_u32(input)? as u64;
+		let count = read_u32(input)? as u64;
+		// Each entry takes at least its id length and its vector
+		let min_len = count
+			.checked_mul(4 + dims * 4)
+			.ok_or_else(|| invalid("vector index header out of range"))?;
+		if min_len > file_len {
+			return Err(invalid("vector index is truncated"));
+		}
+		let (dims, count) = (dims as usize, count as usize);
+
+		let mut ids = Vec::with_capacity(count);
+		let mut vectors = Vec::with_capacity(count * dims);
+		let mut value = [0u8; 4];
+		for _ in 0..count {
+			ids.push(
+				String::from_utf8(read_bytes(input, file_len)?).map_err(|_| invalid("invalid id"))?,
+			);
+			for _ in 0..dims {
+				input.read_exact(&mut value)?;
+				vectors.push(f32::from_le_bytes(value));
+			}
+		}
+
+		Ok(VectorIndex {
+			model,
+			dims,
+			positions: positions_of(&ids),
+			ids,
+			vectors,
+		})
+	}
+}
+
+fn positions_of(ids: &[String]) -> HashMap<String, usize> {
+	ids.iter()
+		.enumerate()
+		.map(|(position, id)| (id.clone(), position))
+		.collect()
+}
+
+fn write_bytes(out: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+	out.write_all(&(bytes.len() as u32).to_le_bytes())?;
+	out.write_all(bytes)
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+	let mut bytes = [0u8; 4];
+	input.read_exact(&mut bytes)?;
+	Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_bytes(input: &mut impl Read, file_len: u64) -> io::Result<Vec<u8>> {
+	let len = read_u32(input)?;
+	if len as u64 > file_len {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"length exceeds the file size",
+		));
+	}
+	let mut bytes = vec![0u8; len as usize];
+	input.read_exact(&mut bytes)?;
+	Ok(bytes)
+}
+
+fn normalize(vector: &[f32]) -> Vec<f32> {
+	let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+	if norm == 0.0 {
+		vector.to_vec()
+	} else {
+		vector.iter().map(|x| x / norm).collect()
+	}
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+	a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn encode(index: &VectorIndex) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		index.write_to(&mut bytes).unwrap();
+		bytes
+	}
+
+	fn decode(bytes: &[u8]) -> io::Result<VectorIndex> {
+		VectorIndex::read_from(&mut &bytes[..], bytes.len() as u64)
+	}
+
+	// A header for model "m" whose length field claims `model_len` bytes
+	fn header(model_len: u32, dims: u32, count: u32) -> Vec<u8> {
+		let model = b"m";
+		let mut bytes = INDEX_MAGIC.to_vec();
+		bytes.extend_from_slice(&INDEX_VERSION.to_le_bytes());
+		bytes.extend_from_slice(&model_len.to_le_bytes());
+		bytes.extend_from_slice(model);
+		bytes.extend_from_slice(&dims.to_le_bytes());
+		bytes.extend_from_slice(&count.to_le_bytes());
+		bytes
+	}
+
+	#[test]
+	fn round_trips_through_the_file_format() {
+		let mut index = VectorIndex::new("nomic-embed-text");
+		index.upsert("a", &[1.0, 0.0, 0.0]).unwrap();
+		index.upsert("b", &[0.0, 2.0, 0.0]).unwrap();
+
+		let loaded = decode(&encode(&index)).unwrap();
+		assert_eq!(loaded.model(), "nomic-embed-text");
+		assert_eq!(loaded.ids().collect::<Vec<_>>(), ["a", "b"]);
+		assert_eq!(loaded.search(&[0.0, 1.0, 0.0], 1)[0].0, "b");
+	}
+
+	#[test]
+	fn upsert_replaces_by_id_after_retain() {
+		let mut index = VectorIndex::new("m");
+		for id in ["a", "b", "c"] {
+			index.upsert(id, &[1.0, 0.0]).unwrap();
+		}
+		index.retain(|id| id != "a");
+		index.upsert("c", &[0.0, 1.0]).unwrap();
+		assert_eq!(index.len(), 2);
+		assert!(!index.contains("a"));
+		assert_eq!(index.search(&[0.0, 1.0], 1)[0].0, "c");
+	}
+
+	#[test]
+	fn rejects_a_wrong_magic_or_version() {
+		let mut bytes = encode(&VectorIndex::new("m"));
+		bytes[0] = b'X';
+		assert_eq!(decode(&bytes).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+		let mut bytes = encode(&VectorIndex::new("m"));
+		bytes[4] = 99;
+		assert_eq!(decode(&bytes).unwrap_err().kind(), io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn rejects_counts_larger_than_the_file() {
+		let error = decode(&header(1, 768, u32::MAX)).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		assert_eq!(error.to_string(), "vector index is truncated");
+	}
+
+	#[test]
+	fn rejects_lengths_larger_than_the_file() {
+		let error = decode(&header(u32::MAX, 0, 0)).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn fails_on_a_truncated_entry() {
+		let mut index = VectorIndex::new("m");
+		index.upsert("a", &[1.0, 0.0]).unwrap();
+		let bytes = encode(&index);
+		let error = decode(&bytes[..bytes.len() - 2]).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+	}
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{ollama, tray};
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaHealth {
+	pub reachable: bool,
+	pub version: Option<String>,
+	// Models currently loaded in memory, from /api/ps
+	pub loaded_models: Vec<String>,
+	pub error: Option<String>,
+}
+
+impl OllamaHealth {
+	// One line for the tray menu and tooltip
+	pub fn summary(&self) -> String {
+		if !self.reachable {
+			return "Ollama: not running".to_string();
+		}
+		let version = self
+			.version
+			.as_deref()
+			.map(|version| format!(" {}", version))
+			.unwrap_or_default();
+		match self.loaded_models.as_slice() {
+			[] => format!("Ollama{}: connected", version),
+			[model] => format!("Ollama{}: {} loaded", version, model),
+			models => format!("Ollama{}: {} models loaded", version, models.len()),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionResponse {
+	version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunningModel {
+	name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunningModelsResponse {
+	#[serde(default)]
+	models: Vec<RunningModel>,
+}
+
+// Last probe result; starts as unreachable until the first check finishes
+#[derive(Default)]
+pub struct HealthState {
+	current: Mutex<OllamaHealth>,
+}
+
+impl HealthState {
+	pub fn current(&self) -> OllamaHealth {
+		self.current.lock().expect("health mutex poisoned").clone()
+	}
+
+	// Store a new result, returning true when it differs from the previous one
+	fn update(&self, health: OllamaHealth) -> bool {
+		let mut current = self.current.lock().expect("health mutex poisoned");
+		if *current == health {
+			return false;
+		}
+		*current = health;
+		true
+	}
+}
+
+// Probe /api/version for reachability and /api/ps for loaded models
+pub async fn probe() -> OllamaHealth {
+	let client = match reqwest::Client::builder().timeout(HEALTH_CHECK_TIMEOUT).build() {
+		Ok(client) => client,
+		Err(e) => {
+			return OllamaHealth {
+				error: Some(format!("Failed to create HTTP client: {}", e)),
+				..OllamaHealth::default()
+			}
+		}
+	};
+
+	let version = match client
+		.get(format!("{}/api/version", ollama::base_url()))
+		.send()
+		.await
+	{
+		Ok(response) if response.status().is_success() => response
+			.bytes()
+			.await
+			.ok()
+			.and_then(|body| serde_json::from_slice::<VersionResponse>(&body).ok())
+			.map(|body| body.version),
+		Ok(response) => {
+			return OllamaHealth {
+				error: Some(format!("Ollama API error: {}", response.status())),
+				..OllamaHealth::default()
+			}
+		}
+		Err(e) => {
+			return OllamaHealth {
+				error: Some(format!("Failed to connect to Ollama: {}", e)),
+				..OllamaHealth::default()
+			}
+		}
+	};
+
+	let loaded_models = match client.get(format!("{}/api/ps", ollama::base_url())).send().await {
+		Ok(response) if response.status().is_success() => response
+			.bytes()
+			.await
+			.ok()
+			.and_then(|body| serde_json::from_slice::<RunningModelsResponse>(&body).ok())
+			.map(|running| running.models.into_iter().map(|model| model.name).collect())
+			.unwrap_or_default(),
+		_ => Vec::new(),
+	};
+
+	OllamaHealth {
+		reachable: true,
+		version,
+		loaded_models,
+		error: None,
+	}
+}
+
+// Probe now and publish the result if it changed
+pub async fn check(app: &AppHandle) -> OllamaHealth {
+	let health = probe().await;
+	if app.state::<HealthState>().update(health.clone()) {
+		log::info!("[health] {}", health.summary());
+		let _ = app.emit("ollama://health", health.clone());
+		tray::refresh(app);
+	}
+	health
+}
+
+// Keep the tray and panel informed about Ollama without waiting for a query to fail
+pub fn spawn_monitor(app: AppHandle) {
+	tauri::async_runtime::spawn(async move {
+		loop {
+			check(&app).await;
+			tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+		}
+	});
+}
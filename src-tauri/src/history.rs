@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::embeddings::{self, VectorIndex};
+
+const HISTORY_FILE_NAME: &str = "history.json";
+const VECTORS_FILE_NAME: &str = "history_vectors.bin";
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+	pub id: u64,
+	pub query: String,
+	pub answer: String,
+	pub model: String,
+	pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HistoryHit {
+	#[serde(flatten)]
+	pub entry: HistoryEntry,
+	pub score: f32,
+}
+
+// Past quick answers, persisted as JSON with a parallel vector index for semantic lookup
+pub struct History {
+	path: PathBuf,
+	vectors_path: PathBuf,
+	entries: Mutex<Vec<HistoryEntry>>,
+	vectors: Mutex<VectorIndex>,
+}
+
+impl History {
+	pub fn load(data_dir: &Path, embedding_model: &str) -> Self {
+		let path = data_dir.join(HISTORY_FILE_NAME);
+		let vectors_path = data_dir.join(VECTORS_FILE_NAME);
+		let entries = read_entries(&path).unwrap_or_default();
+		let mut vectors = VectorIndex::load(&vectors_path, embedding_model);
+		// Ids start over at 1 once history.json is gone, so vectors of the old entries would match the new ones
+		if entries.is_empty() && !vectors.is_empty() {
+			log::info!("[history] History is empty; discarding its old vectors");
+			vectors = VectorIndex::new(embedding_model);
+			if let Err(e) = std::fs::remove_file(&vectors_path) {
+				log::warn!("[history] Failed to remove {}: {}", vectors_path.display(), e);
+			}
+		}
+
+		History {
+			vectors: Mutex::new(vectors),
+			path,
+			vectors_path,
+			entries: Mutex::new(entries),
+		}
+	}
+
+	// The CLI appends to the same file while the app is running, so entries are re-read from disk before use
+	fn synced_entries(&self) -> MutexGuard<'_, Vec<HistoryEntry>> {
+		let mut entries = self.entries.lock().expect("history mutex poisoned");
+		if let Some(on_disk) = read_entries(&self.path) {
+			*entries = on_disk;
+		}
+		entries
+	}
+
+	// Append an entry, dropping the oldest ones past the cap
+	pub fn record(&self, query: &str, answer: &str, model: &str) -> Result<HistoryEntry, String> {
+		let created_at = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|duration| duration.as_secs())
+			.unwrap_or(0);
+
+		let mut entries = self.synced_entries();
+		let entry = HistoryEntry {
+			id: entries.last().map(|last| last.id + 1).unwrap_or(1),
+			query: query.trim().to_string(),
+			answer: answer.to_string(),
+			model: model.to_string(),
+			created_at,
+		};
+		entries.push(entry.clone());
+
+		if entries.len() > MAX_ENTRIES {
+			let excess = entries.len() - MAX_ENTRIES;
+			entries.drain(..excess);
+			let mut vectors = self.vectors.lock().expect("history vectors mutex poisoned");
+			let oldest_kept = entries[0].id;
+			vectors.retain(|id| id.parse::<u64>().map(|id| id >= oldest_kept).unwrap_or(false));
+		}
+
+		let json = serde_json::to_vec(&*entries)
+			.map_err(|e| format!("Failed to serialize history: {}", e))?;
+		if let Some(parent) = self.path.parent() {
+			std::fs::create_dir_all(parent)
+				.map_err(|e| format!("Failed to create history directory: {}", e))?;
+		}
+		std::fs::write(&self.path, json).map_err(|e| format!("Failed to write history: {}", e))?;
+		Ok(entry)
+	}
+
+	pub fn get(&self, id: u64) -> Option<HistoryEntry> {
+		let entries = self.synced_entries();
+		entries.iter().find(|entry| entry.id == id).cloned()
+	}
+
+	// Newest entries first
+	pub fn recent(&self, limit: usize) -> Vec<HistoryEntry> {
+		let entries = self.synced_entries();
+		entries.iter().rev().take(limit).cloned().collect()
+	}
+
+	// Embed every entry that has no vector yet, rebuilding from scratch if the model changed
+	pub async fn embed_missing(&self, model: &str) -> Result<(), String> {
+		let pending: Vec<HistoryEntry> = {
+			let entries = self.entries.lock().expect("history mutex poisoned");
+			let mut vectors = self.vectors.lock().expect("history vectors mutex poisoned");
+			if vectors.model() != model {
+				*vectors = VectorIndex::new(model);
+			}
+			entries
+				.iter()
+				.filter(|entry| !vectors.contains(&entry.id.to_string()))
+				.cloned()
+				.collect()
+		};
+		if pending.is_empty() {
+			return Ok(());
+		}
+
+		let client = reqwest::Client::new();
+		let texts: Vec<String> = pending
+			.iter()
+			.map(|entry| format!("{}\n{}", entry.query, entry.answer))
+			.collect();
+		let embedded = embeddings::embed_texts(&client, model, &texts).await?;
+
+		let mut vectors = self.vectors.lock().expect("history vectors mutex poisoned");
+		if vectors.model() != model {
+			return Ok(());
+		}
+		for (entry, vector) in pending.iter().zip(embedded) {
+			vectors.upsert(&entry.id.to_string(), &vector)?;
+		}
+		vectors.save(&self.vectors_path)
+	}
+
+	pub fn semantic_search(&self, query_embedding: &[f32], limit: usize) -> Vec<HistoryHit> {
+		let entries = self.entries.lock().expect("history mutex poisoned");
+		let vectors = self.vectors.lock().expect("history vectors mutex poisoned");
+		vectors
+			.search(query_embedding, limit)
+			.into_iter()
+			.filter_map(|(id, score)| {
+				let id: u64 = id.parse().ok()?;
+				let entry = entries.iter().find(|entry| entry.id == id)?;
+				Some(HistoryHit {
+					entry: entry.clone(),
+					score,
+				})
+			})
+			.collect()
+	}
+}
+
+fn read_entries(path: &Path) -> Option<Vec<HistoryEntry>> {
+	std::fs::read(path)
+		.ok()
+		.and_then(|bytes| serde_json::from_slice(&bytes).ok())
+}
+
+// Store a finished quick answer and embed it in the background when an embedding model is configured
+pub fn record_quick_answer(app: &AppHandle, query: &str, answer: &str, model: &str) {
+	let history = app.state::<History>();
+	if let Err(e) = history.record(query, answer, model) {
+		log::warn!("[history] Failed to record quick answer: {}", e);
+		return;
+	}
+
+	let Some(embedding_model) = embeddings::configured_model(app) else {
+		return;
+	};
+	let app = app.clone();
+	tauri::async_runtime::spawn(async move {
+		let history = app.state::<History>();
+		if let Err(e) = history.embed_missing(&embedding_model).await {
+			log::warn!("[history] Failed to embed history: {}", e);
+		}
+	});
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Mutex,
+};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+use crate::{clipboard, history, profiles, settings, warmup};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+	TogglePanel,
+	Translate,
+	AskClipboard,
+	RepeatLastQuery,
+	NextProfile,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HotkeyBinding {
+	pub shortcut: String,
+	pub action: HotkeyAction,
+}
+
+// Option+Space on macOS; Alt+Space opens the window menu on Windows and many Linux desktops
+#[cfg(target_os = "macos")]
+const DEFAULT_TOGGLE_SHORTCUT: &str = "Alt+Space";
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_TOGGLE_SHORTCUT: &str = "Ctrl+Shift+Space";
+
+pub fn default_bindings() -> Vec<HotkeyBinding> {
+	vec![HotkeyBinding {
+		shortcut: DEFAULT_TOGGLE_SHORTCUT.to_string(),
+		action: HotkeyAction::TogglePanel,
+	}]
+}
+
+// Shortcuts currently registered with the OS, and the action each one triggers
+#[derive(Default)]
+pub struct HotkeyState {
+	registered: Mutex<Vec<(Shortcut, HotkeyAction)>>,
+	// Set from the tray; while paused nothing is registered with the OS
+	paused: AtomicBool,
+}
+
+// Bindings from the `hotkeys` setting, falling back to the default toggle shortcut
+pub fn configured_bindings(app: &AppHandle) -> Vec<HotkeyBinding> {
+	settings::read::<Vec<HotkeyBinding>>(app, "hotkeys")
+		.filter(|bindings| !bindings.is_empty())
+		.unwrap_or_else(default_bindings)
+}
+
+// Parse every binding before touching the OS registrations so a typo never leaves the app without a hotkey
+pub fn parse_bindings(bindings: &[HotkeyBinding]) -> Result<Vec<(Shortcut, HotkeyAction)>, String> {
+	let mut parsed: Vec<(Shortcut, HotkeyAction)> = Vec::with_capacity(bindings.len());
+	for binding in bindings {
+		let shortcut: Shortcut = binding
+			.shortcut
+			.trim()
+			.parse()
+			.map_err(|e| format!("Invalid shortcut \"{}\": {}", binding.shortcut, e))?;
+		if parsed.iter().any(|(existing, _)| existing == &shortcut) {
+			return Err(format!("Shortcut \"{}\" is bound more than once", binding.shortcut));
+		}
+		parsed.push((shortcut, binding.action));
+	}
+	Ok(parsed)
+}
+
+// Replace the registered shortcuts with the given bindings at runtime
+pub fn apply(app: &AppHandle, bindings: &[HotkeyBinding]) -> Result<(), String> {
+	let parsed = parse_bindings(bindings)?;
+
+	// Already in place, e.g. when `update_hotkeys` announces the bindings it just registered
+	let state = app.state::<HotkeyState>();
+	if *state.registered.lock().expect("hotkey mutex poisoned") == parsed {
+		return Ok(());
+	}
+
+	let global_shortcut = app.global_shortcut();
+	global_shortcut
+		.unregister_all()
+		.map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+
+	let mut active = Vec::with_capacity(parsed.len());
+	let mut failures = Vec::new();
+	for (binding, (shortcut, action)) in bindings.iter().zip(parsed) {
+		match global_shortcut.register(shortcut) {
+			Ok(()) => active.push((shortcut, action)),
+			Err(e) => failures.push(format!("{}: {}", binding.shortcut, e)),
+		}
+	}
+	log::info!(
+		"[hotkeys] Registered {} shortcut(s), {} failed",
+		active.len(),
+		failures.len()
+	);
+
+	// Only lock once registration is done; the shortcut handler takes this lock on key press
+	*state.registered.lock().expect("hotkey mutex poisoned") = active;
+
+	if failures.is_empty() {
+		Ok(())
+	} else {
+		Err(format!("Failed to register shortcuts: {}", failures.join("; ")))
+	}
+}
+
+// Whether any shortcut is registered with the OS
+pub fn has_registered(app: &AppHandle) -> bool {
+	!app.state::<HotkeyState>()
+		.registered
+		.lock()
+		.expect("hotkey mutex poisoned")
+		.is_empty()
+}
+
+pub fn is_paused(app: &AppHandle) -> bool {
+	app.state::<HotkeyState>().paused.load(Ordering::Relaxed)
+}
+
+// Release every shortcut so other apps can use them, or register the configured ones again
+pub fn set_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+	app.state::<HotkeyState>().paused.store(paused, Ordering::Relaxed);
+	if !paused {
+		return apply(app, &configured_bindings(app));
+	}
+
+	app.global_shortcut()
+		.unregister_all()
+		.map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+	app.state::<HotkeyState>()
+		.registered
+		.lock()
+		.expect("hotkey mutex poisoned")
+		.clear();
+	log::info!("[hotkeys] Paused");
+	Ok(())
+}
+
+// Called from the global shortcut plugin handler on key press
+pub fn handle(app: &AppHandle, shortcut: &Shortcut) {
+	let action = {
+		let state = app.state::<HotkeyState>();
+		let registered = state.registered.lock().expect("hotkey mutex poisoned");
+		registered
+			.iter()
+			.find(|(registered_shortcut, _)| registered_shortcut == shortcut)
+			.map(|(_, action)| *action)
+	};
+
+	if let Some(action) = action {
+		log::info!("[hotkeys] Triggered {:?}", action);
+		run_action(app, action);
+	}
+}
+
+pub fn run_action(app: &AppHandle, action: HotkeyAction) {
+	match action {
+		HotkeyAction::TogglePanel => {
+			if crate::toggle_spotlight(app) {
+				let _ = app.emit_to("spotlight", "spotlight://mode", "all");
+				warmup::trigger(app);
+			}
+		}
+		HotkeyAction::Translate => {
+			crate::show_spotlight(app);
+			let _ = app.emit_to("spotlight", "spotlight://mode", "translate");
+		}
+		HotkeyAction::AskClipboard => clipboard::trigger(app),
+		HotkeyAction::RepeatLastQuery => {
+			let last_query = app
+				.state::<history::History>()
+				.recent(1)
+				.pop()
+				.map(|entry| entry.query);
+			match last_query {
+				Some(text) => crate::show_spotlight_with_query(app, text, "ask", true),
+				None => crate::show_spotlight(app),
+			}
+		}
+		HotkeyAction::NextProfile => {
+			let message = match profiles::cycle(app) {
+				Ok(name) => format!("Profile: {}", name.as_deref().unwrap_or("Default")),
+				Err(e) => e,
+			};
+			tauri::async_runtime::spawn(crate::show_toast(app.clone(), message));
+		}
+	}
+}
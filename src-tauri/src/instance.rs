@@ -0,0 +1,150 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::hotkeys::{self, HotkeyAction};
+use crate::{deeplink, warmup};
+
+// Arguments of the app binary, from its own launch or forwarded by a second one: `--show`, `--toggle`,
+// `--ask [TEXT]` and `--translate [TEXT]`. `ai-spotlight://` links among them are handled by the deep link plugin instead.
+
+#[derive(Debug)]
+enum LaunchRequest {
+	Show,
+	// For desktop keybindings, where the global shortcut can't be registered (Wayland)
+	Toggle,
+	Ask(Option<String>),
+	Translate(Option<String>),
+}
+
+fn is_deep_link(arg: &str) -> bool {
+	tauri::Url::parse(arg).is_ok_and(|url| url.scheme() == deeplink::SCHEME)
+}
+
+fn parse(args: &[String]) -> Result<Option<LaunchRequest>, String> {
+	let mut args = args.iter().filter(|arg| !is_deep_link(arg));
+	let Some(flag) = args.next() else {
+		return Ok(None);
+	};
+	let text = args.cloned().collect::<Vec<_>>().join(" ");
+	let text = Some(text).filter(|text| !text.trim().is_empty());
+	match flag.as_str() {
+		"--show" => Ok(Some(LaunchRequest::Show)),
+		"--toggle" => Ok(Some(LaunchRequest::Toggle)),
+		"--ask" => Ok(Some(LaunchRequest::Ask(text))),
+		"--translate" => Ok(Some(LaunchRequest::Translate(text))),
+		other => Err(format!("Unknown argument \"{}\"", other)),
+	}
+}
+
+// What this launch's arguments asked for, kept until the panel has loaded and can receive it
+#[derive(Default)]
+pub struct PendingLaunch(Mutex<Option<LaunchRequest>>);
+
+pub fn queue_launch_args(app: &AppHandle) {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	match parse(&args) {
+		Ok(Some(request)) => {
+			let pending = app.state::<PendingLaunch>();
+			*pending.0.lock().expect("pending launch mutex poisoned") = Some(request);
+		}
+		Ok(None) => {}
+		Err(e) => log::warn!("[instance] Ignoring launch arguments: {}", e),
+	}
+}
+
+// Called by the panel once it listens for `spotlight://query`
+pub fn open_pending(app: &AppHandle) {
+	let request = app
+		.state::<PendingLaunch>()
+		.0
+		.lock()
+		.expect("pending launch mutex poisoned")
+		.take();
+	if let Some(request) = request {
+		run(app, request);
+	}
+}
+
+// The single-instance plugin calls this in the running app when the binary is launched again, before the second
+// process exits. Launching without arguments, e.g. from the app menu, just shows the panel. A link has already
+// been handed to the deep link plugin, which fills in its query; showing the panel again would clear it.
+pub fn handle_second_launch(app: &AppHandle, argv: Vec<String>) {
+	let args = argv.get(1..).unwrap_or_default();
+	if args.iter().any(|arg| is_deep_link(arg)) {
+		return;
+	}
+	let request = match parse(args) {
+		Ok(Some(request)) => request,
+		Ok(None) => LaunchRequest::Show,
+		Err(e) => {
+			log::warn!("[instance] Ignoring forwarded arguments: {}", e);
+			return;
+		}
+	};
+	log::info!(
+		"[instance] Second launch forwarded {}",
+		match &request {
+			LaunchRequest::Show => "show",
+			LaunchRequest::Toggle => "toggle",
+			LaunchRequest::Ask(_) => "ask",
+			LaunchRequest::Translate(_) => "translate",
+		}
+	);
+	run(app, request);
+}
+
+fn run(app: &AppHandle, request: LaunchRequest) {
+	// The panel is shown from the main thread, like the hotkey handler does
+	let handle = app.clone();
+	let result = app.run_on_main_thread(move || match request {
+		LaunchRequest::Show => {
+			crate::show_spotlight(&handle);
+			let _ = handle.emit_to("spotlight", "spotlight://mode", "all");
+			warmup::trigger(&handle);
+		}
+		LaunchRequest::Toggle => hotkeys::run_action(&handle, HotkeyAction::TogglePanel),
+		LaunchRequest::Ask(None) => {
+			crate::show_spotlight(&handle);
+			let _ = handle.emit_to("spotlight", "spotlight://mode", "ask");
+			warmup::trigger(&handle);
+		}
+		LaunchRequest::Ask(Some(text)) => {
+			crate::show_spotlight_with_query(&handle, text, "ask", true);
+		}
+		LaunchRequest::Translate(None) => hotkeys::run_action(&handle, HotkeyAction::Translate),
+		LaunchRequest::Translate(Some(text)) => {
+			crate::show_spotlight_with_query(&handle, text, "translate", true);
+		}
+	});
+	if let Err(e) = result {
+		log::error!("[instance] Failed to show the panel: {}", e);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(values: &[&str]) -> Vec<String> {
+		values.iter().map(|value| value.to_string()).collect()
+	}
+
+	#[test]
+	fn parses_flags_and_text() {
+		assert!(matches!(parse(&args(&[])), Ok(None)));
+		assert!(matches!(parse(&args(&["--toggle"])), Ok(Some(LaunchRequest::Toggle))));
+		assert!(matches!(parse(&args(&["--ask"])), Ok(Some(LaunchRequest::Ask(None)))));
+		match parse(&args(&["--translate", "Bonjour", "tout"])) {
+			Ok(Some(LaunchRequest::Translate(Some(text)))) => assert_eq!(text, "Bonjour tout"),
+			other => panic!("unexpected {:?}", other),
+		}
+		assert!(parse(&args(&["--bogus"])).is_err());
+	}
+
+	#[test]
+	fn leaves_deep_links_to_the_plugin() {
+		assert!(is_deep_link("ai-spotlight://ask?q=hi"));
+		assert!(!is_deep_link("--ask"));
+		assert!(matches!(parse(&args(&["ai-spotlight://ask?q=hi"])), Ok(None)));
+	}
+}
@@ -0,0 +1,709 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Mutex,
+};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::embeddings::{self, VectorIndex};
+use crate::{settings, Tool, ToolFunction};
+
+pub const SEARCH_TOOL_NAME: &str = "search_local_docs";
+
+// How often the configured folders are re-scanned for changed files
+pub const REINDEX_INTERVAL: Duration = Duration::from_secs(30);
+// Longest wait between embedding attempts while Ollama keeps failing
+const MAX_EMBED_RETRY_DELAY: Duration = Duration::from_secs(30 * 60);
+
+const INDEX_FILE_NAME: &str = "local_docs_index.json";
+const VECTORS_FILE_NAME: &str = "local_docs_vectors.bin";
+const SUPPORTED_EXTENSIONS: &[&str] = &["md", "markdown", "txt"];
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const CHUNK_TARGET_CHARS: usize = 1200;
+const CHUNK_MAX_CHARS: usize = 2400;
+const TOOL_RESULT_LIMIT: usize = 5;
+
+// BM25 tuning, the usual defaults
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Chunk {
+	heading: String,
+	text: String,
+	// Term frequencies are cheap to rebuild, so they are not persisted
+	#[serde(skip)]
+	terms: HashMap<String, u32>,
+	#[serde(skip)]
+	length: u32,
+}
+
+impl Chunk {
+	fn new(heading: &str, text: &str) -> Self {
+		let mut chunk = Chunk {
+			heading: heading.to_string(),
+			text: text.trim().to_string(),
+			terms: HashMap::new(),
+			length: 0,
+		};
+		chunk.rebuild_terms();
+		chunk
+	}
+
+	fn rebuild_terms(&mut self) {
+		self.terms.clear();
+		let tokens = tokenize(&format!("{}\n{}", self.heading, self.text));
+		self.length = tokens.len() as u32;
+		for token in tokens {
+			*self.terms.entry(token).or_insert(0) += 1;
+		}
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct IndexedFile {
+	modified: u64,
+	size: u64,
+	chunks: Vec<Chunk>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct DocIndex {
+	#[serde(default)]
+	files: BTreeMap<PathBuf, IndexedFile>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LocalDocsStats {
+	pub files: usize,
+	pub chunks: usize,
+	pub embedded_chunks: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchHit {
+	pub path: String,
+	pub heading: String,
+	pub text: String,
+	pub score: f32,
+}
+
+// Backoff between embedding attempts, so an unreachable Ollama isn't asked again on every poll
+#[derive(Default)]
+struct EmbedRetry {
+	model: String,
+	failures: u32,
+	next_attempt: Option<Instant>,
+}
+
+impl EmbedRetry {
+	// A different model starts over without waiting
+	fn is_due(&mut self, model: &str) -> bool {
+		if self.model != model {
+			*self = EmbedRetry {
+				model: model.to_string(),
+				..Default::default()
+			};
+		}
+		self.next_attempt.is_none_or(|at| Instant::now() >= at)
+	}
+
+	fn record(&mut self, succeeded: bool) {
+		if succeeded {
+			self.reset();
+			return;
+		}
+		self.failures = self.failures.saturating_add(1);
+		let delay = REINDEX_INTERVAL
+			.saturating_mul(2u32.saturating_pow(self.failures - 1))
+			.min(MAX_EMBED_RETRY_DELAY);
+		self.next_attempt = Some(Instant::now() + delay);
+	}
+
+	fn reset(&mut self) {
+		self.failures = 0;
+		self.next_attempt = None;
+	}
+}
+
+pub struct KnowledgeBase {
+	index_path: PathBuf,
+	vectors_path: PathBuf,
+	index: Mutex<DocIndex>,
+	// Vectors searches use, built with the model recorded in the index
+	vectors: Mutex<VectorIndex>,
+	// Vectors for a newly configured model, until every chunk has one
+	staged: Mutex<Option<VectorIndex>>,
+	retry: Mutex<EmbedRetry>,
+	refreshing: AtomicBool,
+}
+
+// Vector ids tie an embedding to a chunk position within a file
+fn chunk_id(path: &Path, position: usize) -> String {
+	format!("{}#{}", path.display(), position)
+}
+
+fn chunk_id_prefix(path: &Path) -> String {
+	format!("{}#", path.display())
+}
+
+impl KnowledgeBase {
+	// Load the persisted index from the app data directory, starting empty if it is missing or corrupt
+	pub fn load(data_dir: &Path, embedding_model: &str) -> Self {
+		let index_path = data_dir.join(INDEX_FILE_NAME);
+		let vectors_path = data_dir.join(VECTORS_FILE_NAME);
+		let mut index = std::fs::read(&index_path)
+			.ok()
+			.and_then(|bytes| serde_json::from_slice::<DocIndex>(&bytes).ok())
+			.unwrap_or_default();
+		for file in index.files.values_mut() {
+			for chunk in &mut file.chunks {
+				chunk.rebuild_terms();
+			}
+		}
+
+		let vectors = VectorIndex::load(&vectors_path, embedding_model);
+
+		KnowledgeBase {
+			index_path,
+			vectors_path,
+			index: Mutex::new(index),
+			vectors: Mutex::new(vectors),
+			staged: Mutex::new(None),
+			retry: Mutex::new(EmbedRetry::default()),
+			refreshing: AtomicBool::new(false),
+		}
+	}
+
+	pub fn has_documents(&self) -> bool {
+		let index = self.index.lock().expect("knowledge index mutex poisoned");
+		index.files.values().any(|file| !file.chunks.is_empty())
+	}
+
+	pub fn stats(&self) -> LocalDocsStats {
+		let index = self.index.lock().expect("knowledge index mutex poisoned");
+		let vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+		LocalDocsStats {
+			files: index.files.len(),
+			chunks: index.files.values().map(|file| file.chunks.len()).sum(),
+			embedded_chunks: vectors.len(),
+		}
+	}
+
+	// Bring the index in line with the configured folders, re-chunking only files whose
+	// size or modification time changed since the last pass, then embed chunks that lack a vector
+	pub async fn refresh(&self, app: &AppHandle) -> Result<LocalDocsStats, String> {
+		if self.refreshing.swap(true, Ordering::AcqRel) {
+			return Ok(self.stats());
+		}
+		let result = self.refresh_inner(app).await;
+		self.refreshing.store(false, Ordering::Release);
+		result
+	}
+
+	async fn refresh_inner(&self, app: &AppHandle) -> Result<LocalDocsStats, String> {
+		let folders: Vec<String> = settings::read(app, "localDocsFolders").unwrap_or_default();
+		let embedding_model = embeddings::configured_model(app).unwrap_or_default();
+
+		let folders: Vec<PathBuf> = folders
+			.iter()
+			.map(|folder| folder.trim())
+			.filter(|folder| !folder.is_empty())
+			.map(PathBuf::from)
+			.collect();
+		let scanned = tauri::async_runtime::spawn_blocking(move || scan_folders(&folders))
+			.await
+			.map_err(|e| format!("Failed to scan local docs: {}", e))?;
+
+		// Work out what changed while holding the lock only briefly
+		let (changed, removed) = {
+			let index = self.index.lock().expect("knowledge index mutex poisoned");
+			let changed: Vec<(PathBuf, u64, u64)> = scanned
+				.iter()
+				.filter(|(path, modified, size)| {
+					index
+						.files
+						.get(path)
+						.map(|file| file.modified != *modified || file.size != *size)
+						.unwrap_or(true)
+				})
+				.cloned()
+				.collect();
+			let removed: Vec<PathBuf> = index
+				.files
+				.keys()
+				.filter(|path| !scanned.iter().any(|(scanned_path, _, _)| scanned_path == *path))
+				.cloned()
+				.collect();
+			(changed, removed)
+		};
+
+		let reindexed = !changed.is_empty() || !removed.is_empty();
+		if reindexed {
+			log::info!(
+				"[local_docs] Re-indexing changed={}, removed={}",
+				changed.len(),
+				removed.len()
+			);
+
+			let rebuilt = tauri::async_runtime::spawn_blocking(move || {
+				changed
+					.into_iter()
+					.map(|(path, modified, size)| {
+						// An unreadable file (e.g. not UTF-8) is kept without chunks, so it's skipped until it changes
+						let chunks = match std::fs::read_to_string(&path) {
+							Ok(content) => chunk_document(&content, is_markdown(&path)),
+							Err(e) => {
+								log::warn!("[local_docs] Skipping {}: {}", path.display(), e);
+								Vec::new()
+							}
+						};
+						(path, IndexedFile { modified, size, chunks })
+					})
+					.collect::<Vec<_>>()
+			})
+			.await
+			.map_err(|e| format!("Failed to read local docs: {}", e))?;
+
+			// Keyword search needs no embeddings, so the chunks go in right away and their vectors follow below
+			let mut index = self.index.lock().expect("knowledge index mutex poisoned");
+			let mut vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+			let mut staged = self.staged.lock().expect("knowledge staged vectors mutex poisoned");
+			let stale: Vec<String> = removed
+				.iter()
+				.chain(rebuilt.iter().map(|(path, _)| path))
+				.map(|path| chunk_id_prefix(path))
+				.collect();
+			let is_stale = |id: &str| stale.iter().any(|prefix| id.starts_with(prefix.as_str()));
+			vectors.retain(|id| !is_stale(id));
+			if let Some(staged) = staged.as_mut() {
+				staged.retain(|id| !is_stale(id));
+			}
+			for path in &removed {
+				index.files.remove(path);
+			}
+			for (path, file) in rebuilt {
+				index.files.insert(path, file);
+			}
+		}
+
+		let embedded = self.embed_missing(&embedding_model).await;
+		if reindexed || embedded {
+			self.save()?;
+		}
+		Ok(self.stats())
+	}
+
+	// Forget earlier embedding failures, so the next refresh tries Ollama again right away
+	pub fn retry_embeddings_now(&self) {
+		self.retry.lock().expect("knowledge retry mutex poisoned").reset();
+	}
+
+	// Embed the chunks that have no vector yet, returning whether the vectors changed. After a model change the
+	// new vectors are staged and replace the old ones only once every chunk has one, so semantic search keeps
+	// working in the meantime.
+	async fn embed_missing(&self, model: &str) -> bool {
+		if model.is_empty() {
+			*self.staged.lock().expect("knowledge staged vectors mutex poisoned") = None;
+			let mut vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+			if vectors.model().is_empty() && vectors.is_empty() {
+				return false;
+			}
+			*vectors = VectorIndex::new("");
+			return true;
+		}
+		if !self.retry.lock().expect("knowledge retry mutex poisoned").is_due(model) {
+			return false;
+		}
+
+		// Missing chunks grouped by file, as (path, ids, texts)
+		let pending: Vec<(PathBuf, Vec<String>, Vec<String>)> = {
+			let index = self.index.lock().expect("knowledge index mutex poisoned");
+			let vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+			let mut staged = self.staged.lock().expect("knowledge staged vectors mutex poisoned");
+			if vectors.model() == model {
+				*staged = None;
+			} else if staged.as_ref().is_none_or(|staged| staged.model() != model) {
+				*staged = Some(VectorIndex::new(model));
+			}
+			let target = staged.as_ref().unwrap_or(&*vectors);
+			let existing: HashSet<&str> = target.ids().collect();
+			index
+				.files
+				.iter()
+				.filter_map(|(path, file)| {
+					let (ids, texts): (Vec<String>, Vec<String>) = file
+						.chunks
+						.iter()
+						.enumerate()
+						.map(|(position, chunk)| (chunk_id(path, position), chunk))
+						.filter(|(id, _)| !existing.contains(id.as_str()))
+						.map(|(id, chunk)| (id, format!("{}\n{}", chunk.heading, chunk.text)))
+						.unzip();
+					(!ids.is_empty()).then(|| (path.clone(), ids, texts))
+				})
+				.collect()
+		};
+
+		// Embed outside the lock, one file at a time so a file Ollama rejects doesn't hold back the others
+		let client = reqwest::Client::new();
+		let mut embedded: Vec<(String, Vec<f32>)> = Vec::new();
+		let mut failed = 0;
+		for (path, ids, texts) in pending {
+			match embeddings::embed_texts(&client, model, &texts).await {
+				Ok(vectors) => embedded.extend(ids.into_iter().zip(vectors)),
+				Err(e) => {
+					if failed == 0 {
+						log::warn!("[local_docs] Embedding failed for {}: {}", path.display(), e);
+					}
+					failed += 1;
+				}
+			}
+		}
+
+		let mut vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+		let mut staged = self.staged.lock().expect("knowledge staged vectors mutex poisoned");
+		let changed = !embedded.is_empty();
+		{
+			let target = match staged.as_mut() {
+				Some(staged) if staged.model() == model => staged,
+				Some(_) => return false,
+				None if vectors.model() == model => &mut *vectors,
+				None => return false,
+			};
+			for (id, vector) in &embedded {
+				if let Err(e) = target.upsert(id, vector) {
+					log::warn!("[local_docs] Skipping embedding for {}: {}", id, e);
+					failed += 1;
+				}
+			}
+		}
+		if failed > 0 {
+			log::warn!("[local_docs] {} embedding(s) failed, retrying later", failed);
+		}
+		self.retry
+			.lock()
+			.expect("knowledge retry mutex poisoned")
+			.record(failed == 0);
+
+		if failed == 0 {
+			if let Some(complete) = staged.take() {
+				log::info!("[local_docs] Switched embeddings to {:?}", model);
+				*vectors = complete;
+				return true;
+			}
+		}
+		// Staged vectors aren't persisted, only the ones searches use
+		changed && staged.is_none()
+	}
+
+	fn save(&self) -> Result<(), String> {
+		let json = {
+			let index = self.index.lock().expect("knowledge index mutex poisoned");
+			serde_json::to_vec(&*index).map_err(|e| format!("Failed to serialize index: {}", e))?
+		};
+		if let Some(parent) = self.index_path.parent() {
+			std::fs::create_dir_all(parent)
+				.map_err(|e| format!("Failed to create index directory: {}", e))?;
+		}
+		std::fs::write(&self.index_path, json).map_err(|e| format!("Failed to write index: {}", e))?;
+
+		let vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+		vectors.save(&self.vectors_path)
+	}
+
+	// Embed the query with the model the stored vectors were built with
+	async fn embed_query(&self, query: &str) -> Option<Vec<f32>> {
+		let model = {
+			let vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+			if vectors.is_empty() {
+				return None;
+			}
+			vectors.model().to_string()
+		};
+		let client = reqwest::Client::new();
+		match embeddings::embed_one(&client, &model, query).await {
+			Ok(embedding) => Some(embedding),
+			Err(e) => {
+				log::warn!("[local_docs] Query embedding failed: {}", e);
+				None
+			}
+		}
+	}
+
+	// Pure vector lookup, used when searching history and docs together
+	pub fn semantic_search(&self, query_embedding: &[f32], limit: usize) -> Vec<SearchHit> {
+		let index = self.index.lock().expect("knowledge index mutex poisoned");
+		let vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+		vectors
+			.search(query_embedding, limit)
+			.into_iter()
+			.filter_map(|(id, score)| {
+				let (path, position) = id.rsplit_once('#')?;
+				let position: usize = position.parse().ok()?;
+				let chunk = index.files.get(Path::new(path))?.chunks.get(position)?;
+				Some(SearchHit {
+					path: path.to_string(),
+					heading: chunk.heading.clone(),
+					text: chunk.text.clone(),
+					score,
+				})
+			})
+			.collect()
+	}
+
+	// Rank chunks with BM25, blending in cosine similarity when embeddings are available
+	pub async fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+		let semantic: HashMap<String, f32> = match self.embed_query(query).await {
+			Some(embedding) => {
+				let vectors = self.vectors.lock().expect("knowledge vectors mutex poisoned");
+				vectors.search(&embedding, vectors.len()).into_iter().collect()
+			}
+			None => HashMap::new(),
+		};
+
+		let index = self.index.lock().expect("knowledge index mutex poisoned");
+		rank_chunks(&index, query, &semantic, limit)
+	}
+
+	// Format search results for the model as the `search_local_docs` tool output
+	pub async fn search_for_tool(&self, query: &str) -> String {
+		let hits = self.search(query, TOOL_RESULT_LIMIT).await;
+		if hits.is_empty() {
+			return "No matching local documents found.".to_string();
+		}
+
+		hits.iter()
+			.enumerate()
+			.map(|(i, hit)| {
+				if hit.heading.is_empty() {
+					format!("[{}] {}\n{}", i + 1, hit.path, hit.text)
+				} else {
+					format!("[{}] {} ({})\n{}", i + 1, hit.path, hit.heading, hit.text)
+				}
+			})
+			.collect::<Vec<_>>()
+			.join("\n\n")
+	}
+}
+
+// Tool definition for searching the local knowledge base
+pub fn search_tool() -> Tool {
+	Tool {
+		tool_type: "function".to_string(),
+		function: ToolFunction {
+			name: SEARCH_TOOL_NAME.to_string(),
+			description: "Search the user's own notes and documents. Use this for questions about their projects, notes or internal documentation.".to_string(),
+			parameters: serde_json::json!({
+				"type": "object",
+				"required": ["query"],
+				"properties": {
+					"query": {
+						"type": "string",
+						"description": "Keywords or a question to look up in the local documents"
+					}
+				}
+			}),
+		},
+	}
+}
+
+// Periodically re-scan the configured folders so edits show up without a restart
+pub fn spawn_watcher(app: AppHandle) {
+	tauri::async_runtime::spawn(async move {
+		loop {
+			let knowledge_base = app.state::<KnowledgeBase>();
+			if let Err(e) = knowledge_base.refresh(&app).await {
+				log::warn!("[local_docs] Refresh failed: {}", e);
+			}
+			tokio::time::sleep(REINDEX_INTERVAL).await;
+		}
+	});
+}
+
+fn is_markdown(path: &Path) -> bool {
+	path.extension()
+		.and_then(|ext| ext.to_str())
+		.is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+// Collect supported files as (path, modified seconds, size), skipping hidden entries
+fn scan_folders(folders: &[PathBuf]) -> Vec<(PathBuf, u64, u64)> {
+	let mut files = Vec::new();
+	let mut pending: Vec<PathBuf> = folders.to_vec();
+
+	while let Some(dir) = pending.pop() {
+		let Ok(entries) = std::fs::read_dir(&dir) else {
+			log::warn!("[local_docs] Cannot read folder {}", dir.display());
+			continue;
+		};
+		for entry in entries.flatten() {
+			let path = entry.path();
+			let hidden = path
+				.file_name()
+				.and_then(|name| name.to_str())
+				.map(|name| name.starts_with('.'))
+				.unwrap_or(true);
+			if hidden {
+				continue;
+			}
+			let Ok(metadata) = entry.metadata() else {
+				continue;
+			};
+			if metadata.is_dir() {
+				pending.push(path);
+				continue;
+			}
+			let supported = path
+				.extension()
+				.and_then(|ext| ext.to_str())
+				.map(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+				.unwrap_or(false);
+			if !supported || metadata.len() > MAX_FILE_BYTES {
+				continue;
+			}
+			let modified = metadata
+				.modified()
+				.ok()
+				.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+				.map(|duration| duration.as_secs())
+				.unwrap_or(0);
+			files.push((path, modified, metadata.len()));
+		}
+	}
+
+	files.sort();
+	files.dedup_by(|a, b| a.0 == b.0);
+	files
+}
+
+// Split a document into paragraph-aligned chunks, tracking the nearest markdown heading
+fn chunk_document(content: &str, markdown: bool) -> Vec<Chunk> {
+	let mut chunks = Vec::new();
+	let mut heading = String::new();
+	let mut current = String::new();
+
+	let flush = |chunks: &mut Vec<Chunk>, heading: &str, current: &mut String| {
+		if !current.trim().is_empty() {
+			chunks.push(Chunk::new(heading, current));
+		}
+		current.clear();
+	};
+
+	for line in content.lines() {
+		let trimmed = line.trim();
+		if markdown && trimmed.starts_with('#') {
+			flush(&mut chunks, &heading, &mut current);
+			heading = trimmed.trim_start_matches('#').trim().to_string();
+			continue;
+		}
+		if trimmed.is_empty() {
+			if current.len() >= CHUNK_TARGET_CHARS {
+				flush(&mut chunks, &heading, &mut current);
+			} else if !current.is_empty() {
+				current.push('\n');
+			}
+			continue;
+		}
+		if current.len() + line.len() > CHUNK_MAX_CHARS {
+			flush(&mut chunks, &heading, &mut current);
+		}
+		current.push_str(line);
+		current.push('\n');
+	}
+	flush(&mut chunks, &heading, &mut current);
+
+	chunks
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+	text.split(|c: char| !c.is_alphanumeric())
+		.filter(|token| token.chars().count() >= 2)
+		.map(|token| token.to_lowercase())
+		.collect()
+}
+
+// Score chunks with BM25, averaging in cosine similarity for chunks that have a vector
+fn rank_chunks(
+	index: &DocIndex,
+	query: &str,
+	semantic: &HashMap<String, f32>,
+	limit: usize,
+) -> Vec<SearchHit> {
+	let mut query_terms = tokenize(query);
+	query_terms.sort();
+	query_terms.dedup();
+
+	let chunks: Vec<(&PathBuf, usize, &Chunk)> = index
+		.files
+		.iter()
+		.flat_map(|(path, file)| {
+			file.chunks
+				.iter()
+				.enumerate()
+				.map(move |(position, chunk)| (path, position, chunk))
+		})
+		.collect();
+	if chunks.is_empty() {
+		return Vec::new();
+	}
+
+	let total = chunks.len() as f32;
+	let avg_length = chunks.iter().map(|(_, _, chunk)| chunk.length as f32).sum::<f32>() / total;
+	let idf: HashMap<&str, f32> = query_terms
+		.iter()
+		.map(|term| {
+			let df = chunks
+				.iter()
+				.filter(|(_, _, chunk)| chunk.terms.contains_key(term))
+				.count() as f32;
+			(term.as_str(), ((total - df + 0.5) / (df + 0.5) + 1.0).ln())
+		})
+		.collect();
+
+	let bm25: Vec<f32> = chunks
+		.iter()
+		.map(|(_, _, chunk)| {
+			let length_norm = 1.0 - BM25_B + BM25_B * chunk.length as f32 / avg_length.max(1.0);
+			query_terms
+				.iter()
+				.map(|term| {
+					let tf = *chunk.terms.get(term).unwrap_or(&0) as f32;
+					idf[term.as_str()] * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * length_norm)
+				})
+				.sum()
+		})
+		.collect();
+	let max_bm25 = bm25.iter().cloned().fold(0.0_f32, f32::max);
+
+	let mut scored: Vec<(f32, usize)> = chunks
+		.iter()
+		.enumerate()
+		.map(|(i, (path, position, _))| {
+			let lexical = if max_bm25 > 0.0 { bm25[i] / max_bm25 } else { 0.0 };
+			match semantic.get(&chunk_id(path, *position)) {
+				Some(similarity) => (0.5 * lexical + 0.5 * similarity.max(0.0), i),
+				None => (lexical, i),
+			}
+		})
+		.filter(|(score, _)| *score > 0.0)
+		.collect();
+	scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+	scored
+		.into_iter()
+		.take(limit)
+		.map(|(score, i)| {
+			let (path, _, chunk) = chunks[i];
+			SearchHit {
+				path: path.display().to_string(),
+				heading: chunk.heading.clone(),
+				text: chunk.text.clone(),
+				score,
+			}
+		})
+		.collect()
+}
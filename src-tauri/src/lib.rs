@@ -5,11 +5,33 @@ use std::sync::{
 	Mutex,
 };
 use std::time::Duration;
-use tauri::{
-	menu::{Menu, MenuItem},
-	tray::TrayIconBuilder,
-	Emitter, Manager, WebviewWindowBuilder,
-};
+use tauri::{Emitter, Listener, Manager, WebviewWindowBuilder};
+
+mod api;
+pub mod cli;
+mod clipboard;
+mod deeplink;
+mod embeddings;
+mod health;
+mod history;
+#[cfg(desktop)]
+mod hotkeys;
+#[cfg(desktop)]
+mod instance;
+mod knowledge;
+#[cfg(target_os = "linux")]
+mod linux;
+mod logging;
+mod ollama;
+mod pipeline;
+mod portable;
+mod profiles;
+mod prompts;
+mod settings;
+mod templates;
+mod tray;
+mod vault;
+mod warmup;
 
 struct RequestSlot {
 	id: u64,
@@ -21,6 +43,7 @@ struct RequestAbortState {
 	counter: AtomicU64,
 	quick_answer: Mutex<Option<RequestSlot>>,
 	translation: Mutex<Option<RequestSlot>>,
+	model_pull: Mutex<Option<RequestSlot>>,
 }
 
 impl RequestAbortState {
@@ -79,6 +102,18 @@ impl RequestAbortState {
 	fn cancel_translation(&self) -> Option<u64> {
 		self.cancel_request(&self.translation)
 	}
+
+	fn start_model_pull(&self) -> (u64, AbortRegistration) {
+		self.start_request(&self.model_pull)
+	}
+
+	fn finish_model_pull(&self, id: u64) {
+		self.finish_request(&self.model_pull, id);
+	}
+
+	fn cancel_model_pull(&self) -> Option<u64> {
+		self.cancel_request(&self.model_pull)
+	}
 }
 
 // Data structures for Ollama API
@@ -125,6 +160,10 @@ struct ChatRequest {
 	model: String,
 	messages: Vec<ChatMessage>,
 	stream: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	options: Option<ollama::GenerationOptions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	keep_alive: Option<ollama::KeepAlive>,
 }
 
 // Extended chat request with tools support
@@ -135,7 +174,12 @@ struct ChatRequestWithTools {
 	stream: bool,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	tools: Option<Vec<Tool>>,
-	think: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	think: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	options: Option<ollama::GenerationOptions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	keep_alive: Option<ollama::KeepAlive>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -144,316 +188,118 @@ struct ChatResponse {
 	done: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ModelInfo {
-	name: String,
+// Command to probe Ollama now, e.g. when the panel opens, instead of waiting for the next scheduled check
+#[tauri::command]
+async fn get_ollama_health(app: tauri::AppHandle) -> Result<health::OllamaHealth, String> {
+	Ok(health::check(&app).await)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ModelsResponse {
-	models: Vec<ModelInfo>,
+// Command to list available models from Ollama, optionally with tool/thinking support from /api/show
+#[tauri::command]
+async fn list_models(include_capabilities: Option<bool>) -> Result<Vec<ollama::ModelInfo>, String> {
+	ollama::list_models(include_capabilities.unwrap_or(false)).await
 }
 
-// Command to list available models from Ollama
+// Command to download a model, reporting progress through `ollama://pull-progress` events
 #[tauri::command]
-async fn list_models() -> Result<Vec<String>, String> {
-	let client = reqwest::Client::new();
-	let response = client
-		.get("http://127.0.0.1:11434/api/tags")
-		.send()
-		.await
-		.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
-
-	// Read response body as bytes and parse JSON manually
-	let body_bytes = response
-		.bytes()
-		.await
-		.map_err(|e| format!("Failed to read response body: {}", e))?;
-
-	let models_response: ModelsResponse = serde_json::from_slice(&body_bytes)
-		.map_err(|e| format!("Failed to parse models response: {}", e))?;
+async fn pull_model(
+	app: tauri::AppHandle,
+	model: String,
+	state: tauri::State<'_, RequestAbortState>,
+) -> Result<(), String> {
+	let model = model.trim().to_string();
+	if model.is_empty() {
+		return Err("Empty model name".to_string());
+	}
 
-	Ok(models_response
-		.models
-		.into_iter()
-		.map(|m| m.name)
-		.collect())
-}
+	let (request_id, abort_registration) = state.start_model_pull();
+	log::info!("[pull_model][id={}] started model={}", request_id, model);
+	let request_future = async { ollama::pull_model(&app, &model).await };
 
-// System prompt for quick AI responses
-const QUICK_ANSWER_SYSTEM_PROMPT: &str = r#"You are a web search agent. Your only job is to answer the user's query using fresh information from the internet.
-
-Rules:
-- Always call the tool `web_search` exactly once per user query.
-- Use the tool results as your primary source of truth.
-- Return a single, direct answer to the user based only on the tool results and common knowledge needed for readability.
-- Do not ask follow-up questions. Do not start or continue a conversation. Do not add suggestions or next steps.
-- If the results are conflicting, summarize the consensus and note uncertainty briefly.
-- If the results are insufficient, say so in one sentence and state what could not be verified.
-
-Output:
-- Respond with only the final answer text (no tool logs, no reasoning, no citations unless the application requires them)."#;
-
-// Web search tool definition
-fn get_web_search_tool() -> Tool {
-	Tool {
-		tool_type: "function".to_string(),
-		function: ToolFunction {
-			name: "web_search".to_string(),
-			description: "Search the internet for current information. Use this when you need to find up-to-date information or facts you don't know.".to_string(),
-			parameters: serde_json::json!({
-				"type": "object",
-				"required": ["query"],
-				"properties": {
-					"query": {
-						"type": "string",
-						"description": "The search query to look up on the internet"
-					}
+	match Abortable::new(request_future, abort_registration).await {
+		Ok(result) => {
+			state.finish_model_pull(request_id);
+			match &result {
+				Ok(_) => {
+					log::info!("[pull_model][id={}] ended ok", request_id);
+					app.state::<ollama::ModelProfiles>().invalidate(&model);
 				}
-			}),
-		},
+				Err(err) => log::info!("[pull_model][id={}] ended error: {}", request_id, err),
+			}
+			result
+		}
+		Err(_) => {
+			state.finish_model_pull(request_id);
+			log::info!("[pull_model][id={}] canceled", request_id);
+			Err("Cancelled".to_string())
+		}
 	}
 }
 
-// Execute web search using the configured search API
-async fn execute_web_search(
-	query: &str,
-	api_url: &str,
-	api_key: &str,
-) -> Result<String, String> {
-	let api_url = api_url.trim();
-	log::info!(
-		"[quick_answer] execute_web_search received url_len={}, has_key={}",
-		api_url.len(),
-		!api_key.trim().is_empty()
-	);
-	if api_url.is_empty() {
-		return Err("Search API URL not configured in Options".to_string());
-	}
-	let api_key = api_key.trim();
-	if api_key.is_empty() {
-		return Err("Search API key not configured in Options".to_string());
+#[tauri::command]
+fn cancel_pull_model(state: tauri::State<'_, RequestAbortState>) -> Result<(), String> {
+	if let Some(request_id) = state.cancel_model_pull() {
+		log::info!("[pull_model][id={}] cancel requested", request_id);
 	}
+	Ok(())
+}
 
-	let client = reqwest::Client::new();
-	let response = client
-		.post(format!("{}?format=json", api_url))
-		.header("Authorization", format!("Bearer {}", api_key))
-		.header("Content-Type", "application/x-www-form-urlencoded")
-		.body(format!("q={}", urlencoding::encode(query)))
-		.send()
-		.await
-		.map_err(|e| format!("Search request failed: {}", e))?;
+// Command to remove a local model
+#[tauri::command]
+async fn delete_model(
+	model: String,
+	model_profiles: tauri::State<'_, ollama::ModelProfiles>,
+) -> Result<(), String> {
+	log::info!("[delete_model] model={}", model);
+	model_profiles.invalidate(model.trim());
+	ollama::delete_model(model.trim()).await
+}
 
-	if !response.status().is_success() {
-		return Err(format!("Search API error: {}", response.status()));
-	}
+// Command to fetch context length, size, quantization and capabilities of a model
+#[tauri::command]
+async fn show_model(model: String) -> Result<ollama::ModelDetails, String> {
+	ollama::show_model(model.trim()).await
+}
 
-	response
-		.text()
-		.await
-		.map_err(|e| format!("Failed to read search response: {}", e))
+// Command to report how requests to a model are shaped, after applying overrides
+#[tauri::command]
+async fn get_model_profile(
+	app: tauri::AppHandle,
+	model: String,
+	model_profiles: tauri::State<'_, ollama::ModelProfiles>,
+) -> Result<ollama::ModelProfile, String> {
+	Ok(model_profiles.resolve(&app, model.trim()).await)
 }
 
 // Command for quick, non-streaming AI response with tool calling support
 #[tauri::command]
 async fn quick_answer(
+	app: tauri::AppHandle,
 	text: String,
-	model: String,
-	enable_thinking: bool,
-	web_search_api_url: Option<String>,
-	web_search_api_key: Option<String>,
 	state: tauri::State<'_, RequestAbortState>,
+	knowledge_base: tauri::State<'_, knowledge::KnowledgeBase>,
+	model_profiles: tauri::State<'_, ollama::ModelProfiles>,
+	last_results: tauri::State<'_, clipboard::LastResults>,
 ) -> Result<String, String> {
+	let history_query = text.clone();
 	let (request_id, abort_registration) = state.start_quick_answer();
-	log::info!("[quick_answer][id={}] started", request_id);
-	let request_future = async move {
-		log::info!("[quick_answer] Called with model={}, enable_thinking={}", model, enable_thinking);
-
-		if text.trim().is_empty() {
-			log::warn!("[quick_answer] Empty text provided");
-			return Err("Empty text".to_string());
-		}
-
-		let search_api_url = web_search_api_url.unwrap_or_default();
-		let search_api_key = web_search_api_key.unwrap_or_default();
-		log::info!(
-			"[quick_answer] Received web search settings url_len={}, has_key={}",
-			search_api_url.trim().len(),
-			!search_api_key.trim().is_empty()
-		);
-
-		let client = reqwest::Client::new();
-		let tools = vec![get_web_search_tool()];
-
-		// Build initial messages
-		// For Qwen3 and similar models, add /no_think or /think suffix to control thinking mode
-		let thinking_suffix = if enable_thinking { " /think" } else { " /no_think" };
-		let user_content = format!("{}{}", text, thinking_suffix);
-
-		let system_msg = serde_json::json!({
-			"role": "system",
-			"content": QUICK_ANSWER_SYSTEM_PROMPT
-		});
-		let user_msg = serde_json::json!({
-			"role": "user",
-			"content": user_content
-		});
-		let mut messages = vec![system_msg, user_msg];
-
-		// First request with tools
-		let request_body = ChatRequestWithTools {
-			model: model.clone(),
-			messages: serde_json::Value::Array(messages.clone()),
-			stream: false,
-			tools: Some(tools.clone()),
-			think: enable_thinking,
-		};
-
-		let json_body = serde_json::to_string(&request_body)
-			.map_err(|e| format!("Failed to serialize request: {}", e))?;
-
-		log::info!("[quick_answer] Sending request to Ollama with think={}", enable_thinking);
-		log::info!("[quick_answer] Full request body: {}", json_body);
-
-		let response = client
-			.post("http://127.0.0.1:11434/api/chat")
-			.header("Content-Type", "application/json")
-			.body(json_body)
-			.send()
-			.await
-			.map_err(|e| {
-				format!(
-					"Failed to connect to Ollama: {}. Make sure Ollama is running.",
-					e
-				)
-			})?;
-
-		if !response.status().is_success() {
-			return Err(format!("Ollama API error: {}", response.status()));
-		}
-
-		let body_bytes = response
-			.bytes()
-			.await
-			.map_err(|e| format!("Failed to read response: {}", e))?;
-
-		let chat_response: ChatResponse = serde_json::from_slice(&body_bytes)
-			.map_err(|e| format!("Failed to parse response: {}", e))?;
-
-		// Check if the model wants to call tools
-		if let Some(ref message) = chat_response.message {
-			if let Some(ref tool_calls) = message.tool_calls {
-				if !tool_calls.is_empty() {
-					// Process tool calls
-					let mut tool_results = Vec::new();
-
-					for tool_call in tool_calls {
-						if tool_call.function.name == "web_search" {
-							// Extract the query from arguments
-							let query = tool_call
-								.function
-								.arguments
-								.get("query")
-								.and_then(|v| v.as_str())
-								.unwrap_or("");
-
-							if !query.is_empty() {
-								log::info!(
-									"[quick_answer] Executing web_search with query=\"{}\"",
-									query
-								);
-								match execute_web_search(query, &search_api_url, &search_api_key)
-									.await
-								{
-									Ok(result) => {
-										tool_results
-											.push((tool_call.function.name.clone(), result));
-									}
-									Err(e) => {
-										log::warn!("[quick_answer] web_search failed: {}", e);
-										tool_results.push((
-											tool_call.function.name.clone(),
-											format!("Search failed: {}", e),
-										));
-									}
-								}
-							}
-						}
-					}
-
-					// Add assistant message with tool calls to conversation
-					let assistant_msg = serde_json::json!({
-						"role": "assistant",
-						"content": message.content.clone(),
-						"tool_calls": message.tool_calls
-					});
-					messages.push(assistant_msg);
-
-					// Add tool results to conversation
-					for (tool_name, result) in tool_results {
-						let tool_msg = serde_json::json!({
-							"role": "tool",
-							"tool_name": tool_name,
-							"content": result
-						});
-						messages.push(tool_msg);
-					}
-
-					// Make second request with tool results
-					let follow_up_request = ChatRequestWithTools {
-						model: model.clone(),
-						messages: serde_json::Value::Array(messages),
-						stream: false,
-						tools: Some(tools),
-						think: enable_thinking,
-					};
-
-					let json_body = serde_json::to_string(&follow_up_request)
-						.map_err(|e| format!("Failed to serialize follow-up request: {}", e))?;
-
-					let response = client
-						.post("http://127.0.0.1:11434/api/chat")
-						.header("Content-Type", "application/json")
-						.body(json_body)
-						.send()
-						.await
-						.map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
-
-					if !response.status().is_success() {
-						return Err(format!("Ollama API error: {}", response.status()));
-					}
-
-					let body_bytes = response
-						.bytes()
-						.await
-						.map_err(|e| format!("Failed to read follow-up response: {}", e))?;
-
-					let final_response: ChatResponse = serde_json::from_slice(&body_bytes)
-						.map_err(|e| format!("Failed to parse follow-up response: {}", e))?;
-
-					return final_response
-						.message
-						.map(|m| m.content)
-						.ok_or_else(|| "No response from model".to_string());
-				}
-			}
-		}
-	// No tool calls, return direct response
-	chat_response
-		.message
-		.map(|m| m.content)
-		.ok_or_else(|| "No response from model".to_string())
-	};
+	log::info!("[quick_answer][id={}] started text={}", request_id, logging::text(&text));
+	let request_future =
+		pipeline::quick_answer(&app, knowledge_base.inner(), model_profiles.inner(), text);
 
 	match Abortable::new(request_future, abort_registration).await {
 		Ok(result) => {
 			state.finish_quick_answer(request_id);
 			match &result {
-				Ok(_) => log::info!("[quick_answer][id={}] ended ok", request_id),
+				Ok(answer) => {
+					log::info!("[quick_answer][id={}] ended ok", request_id);
+					last_results.set(clipboard::ResultKind::Answer, &answer.text);
+					history::record_quick_answer(&app, &history_query, &answer.text, &answer.model);
+					tray::refresh(&app);
+				}
 				Err(err) => log::info!("[quick_answer][id={}] ended error: {}", request_id, err),
 			}
-			result
+			result.map(|answer| answer.text)
 		}
 		Err(_) => {
 			state.finish_quick_answer(request_id);
@@ -479,6 +325,8 @@ async fn chat_stream(
 		model,
 		messages,
 		stream: true,
+		options: ollama::configured_options(&app).non_empty(),
+		keep_alive: ollama::configured_keep_alive(&app),
 	};
 
 	// Serialize request body to JSON manually
@@ -487,7 +335,7 @@ async fn chat_stream(
 
 	// Make the POST request
 	let response = client
-		.post("http://127.0.0.1:11434/api/chat")
+		.post(format!("{}/api/chat", ollama::base_url()))
 		.header("Content-Type", "application/json")
 		.body(json_body)
 		.send()
@@ -553,183 +401,99 @@ fn greet(name: &str) -> String {
 	format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+// Command to read the Options settings as the backend sees them, after validation; secrets are never returned
 #[tauri::command]
-fn log_settings_update(values: serde_json::Value) -> Result<(), String> {
-	let mut safe_values = values;
-	if let Some(obj) = safe_values.as_object_mut() {
-		if obj.contains_key("webSearchApiKey") {
-			obj.insert(
-				"webSearchApiKey".to_string(),
-				serde_json::Value::String("[redacted]".to_string()),
-			);
-		}
-	}
-	log::info!("[settings] Updated values: {}", safe_values);
-	Ok(())
+fn get_settings(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+	settings::Settings::load(&app).public_view()
 }
 
-// Translation result structure
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TranslationResult {
-	pub text: String,
-	pub detected_language: String,
+// Command to validate and save changed Options settings; emits `settings://updated` with the changed keys
+#[tauri::command]
+fn update_settings(
+	app: tauri::AppHandle,
+	values: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+	log::info!("[settings] Updating values: {}", logging::payload(&values));
+	settings::update(&app, &values)?.public_view()
 }
 
-async fn translate_with_target(
-	client: &reqwest::Client,
-	text: &str,
-	target_language: &str,
-) -> Result<TranslationResult, String> {
-	let target_language = target_language.trim();
-
-	// Use the batch translate endpoint (more reliable, less rate-limited)
-	let rpcids = "MkEWBc";
-	let req_id: u32 = rand::random::<u32>() % 9000 + 1000;
-
-	let query_params = format!(
-		"rpcids={}&source-path=/&f.sid=&bl=&hl=en-US&soc-app=1&soc-platform=1&soc-device=1&_reqid={}&rt=c",
-		rpcids, req_id
-	);
-
-	let url = format!(
-		"https://translate.google.com/_/TranslateWebserverUi/data/batchexecute?{}",
-		query_params
-	);
-
-	// Build the request body
-	// Format: [[["MkEWBc","[[\"text\",\"auto\",\"en\",true],[null]]",null,"1"]]]
-	let freq_inner = serde_json::json!([[text, "auto", target_language, true], [null]]);
-	let freq = serde_json::json!([[[rpcids, freq_inner.to_string(), null, "0"]]]);
-	let body = format!("f.req={}&", urlencoding::encode(&freq.to_string()));
-
-	let response = client
-		.post(&url)
-		.header("Content-Type", "application/x-www-form-urlencoded;charset=UTF-8")
-		.body(body)
-		.send()
-		.await
-		.map_err(|e| format!("Request failed: {}", e))?;
-
-	if !response.status().is_success() {
-		return Err(format!("HTTP error: {}", response.status()));
-	}
+// Command to write the settings to a portable file; secrets are only included, encrypted, with a passphrase
+#[tauri::command]
+fn export_settings(
+	app: tauri::AppHandle,
+	path: String,
+	passphrase: Option<String>,
+) -> Result<(), String> {
+	let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+	portable::export(&app, std::path::Path::new(&path), passphrase.as_deref())
+}
 
-	let response_text = response
-		.text()
-		.await
-		.map_err(|e| format!("Failed to read response: {}", e))?;
+// Command to list what importing a settings file would change, without applying it
+#[tauri::command]
+fn preview_settings_import(
+	app: tauri::AppHandle,
+	path: String,
+	mode: portable::ImportMode,
+	passphrase: Option<String>,
+) -> Result<portable::ImportPreview, String> {
+	let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+	portable::preview(&app, std::path::Path::new(&path), mode, passphrase.as_deref())
+}
 
-	// Parse the response - it's in a special format
-	// Skip the first 6 characters (")]}'\n\n")
-	let json_text = if response_text.len() > 6 {
-		&response_text[6..]
-	} else {
-		return Err("Invalid response format".to_string());
-	};
+// Command to import a settings file, merging it into or replacing the current settings
+#[tauri::command]
+fn import_settings(
+	app: tauri::AppHandle,
+	path: String,
+	mode: portable::ImportMode,
+	passphrase: Option<String>,
+) -> Result<portable::ImportPreview, String> {
+	let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+	portable::import(&app, std::path::Path::new(&path), mode, passphrase.as_deref())
+}
 
-	// Find the first valid JSON array line
-	for line in json_text.lines() {
-		if !line.starts_with('[') || line.contains("\"e\"") {
-			continue;
-		}
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfilesInfo {
+	names: Vec<String>,
+	active: Option<String>,
+}
 
-		// Parse the outer array
-		let outer: serde_json::Value = serde_json::from_str(line)
-			.map_err(|e| format!("Failed to parse response JSON: {}", e))?;
-
-		// Navigate the nested structure to find the translation
-		// Structure: [[["wrb.fr", "MkEWBc", "[[...translation data...]]", ...]]]
-		if let Some(arr) = outer.as_array() {
-			for item in arr {
-				if let Some(inner_arr) = item.as_array() {
-					if inner_arr.len() >= 3 {
-						if let Some(wrapper_type) = inner_arr.get(0).and_then(|v| v.as_str()) {
-							if wrapper_type == "wrb.fr" {
-								if let Some(data_str) = inner_arr.get(2).and_then(|v| v.as_str()) {
-									// Parse the inner JSON string
-									let data: serde_json::Value = serde_json::from_str(data_str)
-										.map_err(|e| format!("Failed to parse translation data: {}", e))?;
-
-									// Extract translation and detected language
-									// Structure: [[[null, null, null, [[[0, [[[null, "translated text"]]]...], detected_lang, ...
-									if let Some(translation_data) = data.get(1).and_then(|v| v.get(0)).and_then(|v| v.get(0)).and_then(|v| v.get(5)) {
-										if let Some(parts) = translation_data.as_array() {
-											let mut translated_text = String::new();
-											for part in parts {
-												if let Some(segment) = part.get(0).and_then(|v| v.as_str()) {
-													translated_text.push_str(segment);
-												}
-											}
-
-											// Get detected language
-											let detected_lang = data
-												.get(1)
-												.and_then(|v| v.get(3))
-												.and_then(|v| v.as_str())
-												.or_else(|| data.get(2).and_then(|v| v.as_str()))
-												.unwrap_or("auto")
-												.to_string();
-
-											return Ok(TranslationResult {
-												text: translated_text,
-												detected_language: detected_lang,
-											});
-										}
-									}
-								}
-							}
-						}
-					}
-				}
-			}
-		}
+// Command to list the configured profiles and the active one
+#[tauri::command]
+fn get_profiles(app: tauri::AppHandle) -> ProfilesInfo {
+	ProfilesInfo {
+		names: profiles::names(&app),
+		active: profiles::active(&app),
 	}
+}
 
-	Err("Could not parse translation from response".to_string())
+// Command to apply a profile, or the top-level settings when `name` is null
+#[tauri::command]
+fn switch_profile(app: tauri::AppHandle, name: Option<String>) -> Result<(), String> {
+	profiles::switch(&app, name.as_deref())
 }
 
 // Command to translate text using Google Translate
 #[tauri::command]
 async fn translate_text(
+	app: tauri::AppHandle,
 	text: String,
-	target_language: Option<String>,
 	state: tauri::State<'_, RequestAbortState>,
-) -> Result<TranslationResult, String> {
+	last_results: tauri::State<'_, clipboard::LastResults>,
+) -> Result<pipeline::TranslationResult, String> {
 	let (request_id, abort_registration) = state.start_translation();
-	log::info!("[translate_text][id={}] started", request_id);
-	let request_future = async move {
-		if text.trim().is_empty() {
-			return Err("Empty text".to_string());
-		}
-
-		let client = reqwest::Client::new();
-		let second_language = target_language.unwrap_or_default();
-		let trimmed_language = second_language.trim();
-
-		if trimmed_language.is_empty() || trimmed_language == "en" {
-			let english_result = translate_with_target(&client, &text, "en").await?;
-			if english_result.detected_language == "en" {
-				return Err("Source is English".to_string());
-			}
-			return Ok(english_result);
-		}
-
-		let second_language_result =
-			translate_with_target(&client, &text, trimmed_language).await?;
-
-		if second_language_result.detected_language == "en" {
-			return Ok(second_language_result);
-		}
-
-		let english_result = translate_with_target(&client, &text, "en").await?;
-		Ok(english_result)
-	};
+	log::info!("[translate_text][id={}] started text={}", request_id, logging::text(&text));
+	let request_future = pipeline::translate(&app, &text);
 
 	match Abortable::new(request_future, abort_registration).await {
 		Ok(result) => {
 			state.finish_translation(request_id);
 			match &result {
-				Ok(_) => log::info!("[translate_text][id={}] ended ok", request_id),
+				Ok(translation) => {
+					log::info!("[translate_text][id={}] ended ok", request_id);
+					last_results.set(clipboard::ResultKind::Translation, &translation.text);
+				}
 				Err(err) => log::info!("[translate_text][id={}] ended error: {}", request_id, err),
 			}
 			result
@@ -742,6 +506,74 @@ async fn translate_text(
 	}
 }
 
+// Command to re-scan the local docs folders and retry failed embeddings immediately instead of waiting for the watcher
+#[tauri::command]
+async fn reindex_local_docs(
+	app: tauri::AppHandle,
+	knowledge_base: tauri::State<'_, knowledge::KnowledgeBase>,
+) -> Result<knowledge::LocalDocsStats, String> {
+	knowledge_base.retry_embeddings_now();
+	knowledge_base.refresh(&app).await
+}
+
+// Command to query the local knowledge base directly from the UI
+#[tauri::command]
+async fn search_local_docs(
+	query: String,
+	limit: Option<usize>,
+	knowledge_base: tauri::State<'_, knowledge::KnowledgeBase>,
+) -> Result<Vec<knowledge::SearchHit>, String> {
+	if query.trim().is_empty() {
+		return Err("Empty query".to_string());
+	}
+	Ok(knowledge_base.search(&query, limit.unwrap_or(10)).await)
+}
+
+// Command to list past quick answers, newest first
+#[tauri::command]
+fn list_history(
+	limit: Option<usize>,
+	history: tauri::State<'_, history::History>,
+) -> Result<Vec<history::HistoryEntry>, String> {
+	Ok(history.recent(limit.unwrap_or(50)))
+}
+
+#[derive(Debug, Serialize)]
+struct SemanticSearchResults {
+	history: Vec<history::HistoryHit>,
+	docs: Vec<knowledge::SearchHit>,
+}
+
+// Command for embedding-based lookup over past quick answers and indexed local files
+#[tauri::command]
+async fn semantic_search(
+	app: tauri::AppHandle,
+	query: String,
+	limit: Option<usize>,
+	history: tauri::State<'_, history::History>,
+	knowledge_base: tauri::State<'_, knowledge::KnowledgeBase>,
+) -> Result<SemanticSearchResults, String> {
+	if query.trim().is_empty() {
+		return Err("Empty query".to_string());
+	}
+	let model = embeddings::configured_model(&app)
+		.ok_or_else(|| "Embedding model not configured".to_string())?;
+	let limit = limit.unwrap_or(10);
+
+	// Catch up on answers recorded while Ollama was unreachable before searching
+	if let Err(e) = history.embed_missing(&model).await {
+		log::warn!("[semantic_search] Failed to embed history: {}", e);
+	}
+
+	let client = reqwest::Client::new();
+	let query_embedding = embeddings::embed_one(&client, &model, &query).await?;
+
+	Ok(SemanticSearchResults {
+		history: history.semantic_search(&query_embedding, limit),
+		docs: knowledge_base.semantic_search(&query_embedding, limit),
+	})
+}
+
 #[tauri::command]
 fn cancel_quick_answer(state: tauri::State<'_, RequestAbortState>) -> Result<(), String> {
 	if let Some(request_id) = state.cancel_quick_answer() {
@@ -804,9 +636,9 @@ async fn show_toast(app: tauri::AppHandle, message: String) -> Result<(), String
 			}));
 		}
 
+		// The toast never takes focus: on Linux the panel hides as soon as it loses focus
 		let _ = window.emit("toast://message", message.clone());
 		let _ = window.show();
-		let _ = window.set_focus();
 	} else {
 		// Create the toast window
 		let toast_url = tauri::WebviewUrl::App("index.html?window=toast".into());
@@ -821,6 +653,8 @@ async fn show_toast(app: tauri::AppHandle, message: String) -> Result<(), String
 			.skip_taskbar(true)
 			.shadow(false)
 			.visible(false)
+			.focused(false)
+			.focusable(false)
 			.build()
 			.map_err(|e| format!("Failed to create toast window: {}", e))?;
 
@@ -940,6 +774,241 @@ mod macos {
 	}
 }
 
+// Show the spotlight panel centered on screen, using the native panel on macOS
+fn show_spotlight(app: &tauri::AppHandle) {
+	#[cfg(target_os = "macos")]
+	{
+		// Center the window before showing
+		if let Some(window) = app.get_webview_window("spotlight") {
+			let _ = window.center();
+		}
+		macos::show_panel(app);
+	}
+
+	#[cfg(target_os = "linux")]
+	if let Some(window) = app.get_webview_window("spotlight") {
+		linux::show_panel(app, &window);
+	}
+
+	#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+	{
+		if let Some(window) = app.get_webview_window("spotlight") {
+			let _ = window.center();
+			let _ = window.show();
+			let _ = window.set_focus();
+		}
+	}
+}
+
+// Payload of `spotlight://query`, asking the panel to fill in a query and run it
+#[derive(Debug, Serialize, Clone)]
+struct PanelQuery {
+	text: String,
+	mode: String,
+	// Skip the typing debounce, since the text did not come from the keyboard
+	immediate: bool,
+	// A stored answer to show instead of running the query again
+	answer: Option<String>,
+	// Leave the text in the input until the user presses Enter
+	hold: bool,
+}
+
+fn show_spotlight_with_query(app: &tauri::AppHandle, text: String, mode: &str, immediate: bool) {
+	show_spotlight(app);
+	let _ = app.emit_to(
+		"spotlight",
+		"spotlight://query",
+		PanelQuery {
+			text,
+			mode: mode.to_string(),
+			immediate,
+			answer: None,
+			hold: false,
+		},
+	);
+}
+
+// Fill in a query without running it, for text handed over by other apps
+fn show_spotlight_with_draft(app: &tauri::AppHandle, text: String, mode: &str) {
+	show_spotlight(app);
+	let _ = app.emit_to(
+		"spotlight",
+		"spotlight://query",
+		PanelQuery {
+			text,
+			mode: mode.to_string(),
+			immediate: true,
+			answer: None,
+			hold: true,
+		},
+	);
+}
+
+// Reopen a past quick answer without asking the model again
+fn show_spotlight_with_answer(app: &tauri::AppHandle, text: String, answer: String) {
+	show_spotlight(app);
+	let _ = app.emit_to(
+		"spotlight",
+		"spotlight://query",
+		PanelQuery {
+			text,
+			mode: "ask".to_string(),
+			immediate: true,
+			answer: Some(answer),
+			hold: false,
+		},
+	);
+}
+
+fn hide_spotlight(app: &tauri::AppHandle) {
+	#[cfg(target_os = "macos")]
+	macos::hide_panel(app);
+
+	#[cfg(not(target_os = "macos"))]
+	if let Some(window) = app.get_webview_window("spotlight") {
+		let _ = window.hide();
+	}
+}
+
+fn is_spotlight_visible(app: &tauri::AppHandle) -> bool {
+	#[cfg(target_os = "macos")]
+	{
+		macos::is_panel_visible(app)
+	}
+
+	#[cfg(not(target_os = "macos"))]
+	{
+		app.get_webview_window("spotlight")
+			.and_then(|window| window.is_visible().ok())
+			.unwrap_or(false)
+	}
+}
+
+// Toggle the spotlight panel, returning whether it is visible afterwards
+fn toggle_spotlight(app: &tauri::AppHandle) -> bool {
+	if is_spotlight_visible(app) {
+		hide_spotlight(app);
+		false
+	} else {
+		show_spotlight(app);
+		true
+	}
+}
+
+// Command to open the panel on the current clipboard text, in the given or configured mode
+#[tauri::command]
+fn ask_about_clipboard(
+	app: tauri::AppHandle,
+	mode: Option<clipboard::ClipboardMode>,
+) -> Result<(), String> {
+	clipboard::ask_about_clipboard(&app, mode)
+}
+
+// Command to copy the last answer or translation to the clipboard and confirm with a toast
+#[tauri::command]
+async fn copy_last_result(
+	app: tauri::AppHandle,
+	kind: Option<clipboard::ResultKind>,
+	last_results: tauri::State<'_, clipboard::LastResults>,
+) -> Result<(), String> {
+	let (kind, text) = last_results
+		.get(kind)
+		.ok_or_else(|| "No result to copy".to_string())?;
+	clipboard::write_text(&app, text)?;
+	show_toast(app, kind.copied_message().to_string()).await
+}
+
+// Command to hand a result back to the app the user came from: the panel hides, focus
+// returns to the previous app, and the result waits on the clipboard to be pasted
+#[tauri::command]
+async fn replace_selection(
+	app: tauri::AppHandle,
+	kind: Option<clipboard::ResultKind>,
+	last_results: tauri::State<'_, clipboard::LastResults>,
+) -> Result<(), String> {
+	let (kind, text) = last_results
+		.get(kind)
+		.ok_or_else(|| "No result to paste".to_string())?;
+	log::info!("[replace_selection] kind={:?}, text_len={}", kind, text.len());
+
+	hide_spotlight(&app);
+	// The panel never activates the app on macOS, but the options window can; hiding the
+	// app hands focus back to whatever was frontmost before
+	#[cfg(target_os = "macos")]
+	{
+		let _ = app.hide();
+	}
+
+	clipboard::write_text(&app, text)
+}
+
+// Command to list the configured hotkey bindings
+#[cfg(desktop)]
+#[tauri::command]
+fn get_hotkeys(app: tauri::AppHandle) -> Result<Vec<hotkeys::HotkeyBinding>, String> {
+	Ok(hotkeys::configured_bindings(&app))
+}
+
+// Command to validate, register and persist new hotkey bindings without restarting. Bindings the OS refuses are
+// not saved, and the previous ones are registered again. An empty list stands for the default shortcut. While
+// hotkeys are paused from the tray, the bindings are only saved and get registered on resume.
+#[cfg(desktop)]
+#[tauri::command]
+fn update_hotkeys(
+	app: tauri::AppHandle,
+	bindings: Vec<hotkeys::HotkeyBinding>,
+) -> Result<(), String> {
+	hotkeys::parse_bindings(&bindings)?;
+	if hotkeys::is_paused(&app) {
+		settings::write(&app, "hotkeys", &bindings)?;
+		settings::notify_updated(&app, &["hotkeys"]);
+		return Ok(());
+	}
+	let previous = hotkeys::configured_bindings(&app);
+	let effective = if bindings.is_empty() {
+		hotkeys::default_bindings()
+	} else {
+		bindings.clone()
+	};
+	let result = hotkeys::apply(&app, &effective)
+		.and_then(|()| settings::write(&app, "hotkeys", &bindings));
+	match &result {
+		Ok(()) => settings::notify_updated(&app, &["hotkeys"]),
+		Err(_) => {
+			if let Err(e) = hotkeys::apply(&app, &previous) {
+				log::error!("[hotkeys] Failed to restore the previous shortcuts: {}", e);
+			}
+		}
+	}
+	result
+}
+
+// Command for the panel to run the links and arguments the app was launched with, once it's listening for queries
+#[tauri::command]
+fn open_pending_requests(app: tauri::AppHandle) {
+	deeplink::open_pending(&app);
+	#[cfg(desktop)]
+	instance::open_pending(&app);
+}
+
+// Command to report whether the local API is enabled and listening
+#[tauri::command]
+fn get_local_api_status(app: tauri::AppHandle) -> api::LocalApiStatus {
+	api::status(&app)
+}
+
+// Command to copy the local API token to the clipboard, so the webview never receives it
+#[tauri::command]
+fn copy_local_api_token(app: tauri::AppHandle) -> Result<(), String> {
+	api::copy_token(&app)
+}
+
+// Command to replace the local API token, locking out clients that have the old one
+#[tauri::command]
+fn regenerate_local_api_token(app: tauri::AppHandle) -> Result<(), String> {
+	api::regenerate_token(&app).map(|_| ())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
 	// Load .env file from the project root
@@ -948,7 +1017,17 @@ pub fn run() {
 		eprintln!("Warning: Could not load .env file: {}", e);
 	}
 
-	let mut builder = tauri::Builder::default()
+	let mut builder = tauri::Builder::default();
+
+	// Registered first, so a second launch hands its arguments to this instance and exits before setting anything up
+	#[cfg(desktop)]
+	{
+		builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+			instance::handle_second_launch(app, argv);
+		}));
+	}
+
+	builder = builder
 		.plugin(tauri_plugin_http::init())
 		.plugin(tauri_plugin_store::Builder::new().build())
 		.plugin(tauri_plugin_clipboard_manager::init())
@@ -958,9 +1037,21 @@ pub fn run() {
 				.build(),
 		)
 		.plugin(tauri_plugin_autostart::Builder::new().build())
+		.plugin(tauri_plugin_deep_link::init())
 		.plugin(tauri_plugin_opener::init());
 
-	builder = builder.manage(RequestAbortState::default());
+	builder = builder
+		.manage(RequestAbortState::default())
+		.manage(ollama::ModelProfiles::default())
+		.manage(clipboard::LastResults::default())
+		.manage(warmup::ModelWarmup::default())
+		.manage(health::HealthState::default())
+		.manage(api::LocalApiState::default())
+		.manage(deeplink::PendingLinks::default());
+	#[cfg(desktop)]
+	{
+		builder = builder.manage(instance::PendingLaunch::default());
+	}
 
 	// Add nspanel plugin on macOS
 	#[cfg(target_os = "macos")]
@@ -972,102 +1063,144 @@ pub fn run() {
 		.setup(|app| {
 			// Set activation policy to Accessory (no dock icon, no app switcher)
 			// This is essential for Spotlight-like behavior
+			#[cfg(target_os = "macos")]
 			app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
-			// Create system tray with Options and Exit menu
-			let options_item = MenuItem::with_id(app, "options", "Options", true, None::<&str>)?;
-			let quit_item = MenuItem::with_id(app, "quit", "Exit", true, None::<&str>)?;
-			let menu = Menu::with_items(app, &[&options_item, &quit_item])?;
-
-			let _tray = TrayIconBuilder::new()
-				.icon(app.default_window_icon().unwrap().clone())
-				.menu(&menu)
-				.show_menu_on_left_click(true)
-				.on_menu_event(|app, event| match event.id.as_ref() {
-					"options" => {
-						// Check if options window already exists
-						if let Some(window) = app.get_webview_window("options") {
-							// If it exists, just show and focus it
-							let _ = window.show();
-							let _ = window.set_focus();
-						} else {
-							// Create the options window
-							let options_url =
-								tauri::WebviewUrl::App("index.html?window=options".into());
-							if let Ok(window) =
-								WebviewWindowBuilder::new(app, "options", options_url)
-									.title("Options")
-									.inner_size(560.0, 620.0)
-									.resizable(false)
-									.center()
-									.build()
-							{
-								let _ = window.show();
-								let _ = window.set_focus();
-							}
-						}
+			// Open the secrets vault; without it secret settings read as unset and can't be saved
+			let data_dir = app.path().app_data_dir()?;
+			match vault::Vault::open(&data_dir) {
+				Ok(vault) => {
+					app.manage(vault);
+				}
+				Err(e) => log::error!("[vault] {}", e),
+			}
+
+			// Upgrade settings written by older versions before anything reads them
+			if let Err(e) = settings::migrate(app.handle()) {
+				log::error!("[settings] {}", e);
+			}
+			// Cache the settings that request paths read without an AppHandle
+			logging::configure(app.handle());
+			ollama::configure(app.handle());
+			let config_handle = app.handle().clone();
+			app.listen_any("settings://updated", move |_event| {
+				logging::configure(&config_handle);
+				// Model profiles and warm-ups are keyed by model name alone, so another server starts them over
+				if ollama::configure(&config_handle) {
+					config_handle.state::<ollama::ModelProfiles>().clear();
+					config_handle.state::<warmup::ModelWarmup>().reset();
+				}
+			});
+
+			// Load history and the local docs index, and keep the latter in sync with the configured folders
+			let embedding_model = embeddings::configured_model(app.handle()).unwrap_or_default();
+			app.manage(history::History::load(&data_dir, &embedding_model));
+			app.manage(knowledge::KnowledgeBase::load(&data_dir, &embedding_model));
+			knowledge::spawn_watcher(app.handle().clone());
+			let settings_handle = app.handle().clone();
+			app.listen_any("settings://updated", move |event| {
+				let payload = event.payload();
+				if !payload.contains("\"localDocsFolders\"") && !payload.contains("\"embeddingModel\"") {
+					return;
+				}
+				let handle = settings_handle.clone();
+				tauri::async_runtime::spawn(async move {
+					let knowledge_base = handle.state::<knowledge::KnowledgeBase>();
+					if let Err(e) = knowledge_base.refresh(&handle).await {
+						log::warn!("[local_docs] Refresh after settings update failed: {}", e);
 					}
-					"quit" => {
-						app.exit(0);
+				});
+			});
+
+			// Create the system tray, and watch Ollama so its status shows there before a query fails
+			tray::init(app.handle())?;
+			health::spawn_monitor(app.handle().clone());
+			let tray_handle = app.handle().clone();
+			app.listen_any("settings://updated", move |_event| {
+				tray::refresh(&tray_handle);
+			});
+
+			// Open the panel for `ai-spotlight://` links, including the one the app was launched with
+			{
+				use tauri_plugin_deep_link::DeepLinkExt;
+
+				// Packaged builds register the scheme on install; this covers dev builds and AppImages
+				#[cfg(any(target_os = "linux", windows))]
+				if let Err(e) = app.deep_link().register_all() {
+					log::warn!("[deeplink] Failed to register the URL scheme: {}", e);
+				}
+				let deeplink_handle = app.handle().clone();
+				app.deep_link().on_open_url(move |event| {
+					for url in event.urls() {
+						deeplink::open(&deeplink_handle, &url);
 					}
-					_ => {}
-				})
-				.build(app)?;
+				});
+				if let Ok(Some(urls)) = app.deep_link().get_current() {
+					app.state::<deeplink::PendingLinks>().push(urls);
+				}
+			}
+			// `--ask`, `--translate` or `--show` given to this launch
+			#[cfg(desktop)]
+			instance::queue_launch_args(app.handle());
+
+			// Serve the opt-in local API once everything its endpoints use is managed
+			api::configure(app.handle());
+			let api_handle = app.handle().clone();
+			app.listen_any("settings://updated", move |event| {
+				if event.payload().contains("\"localApi\"") {
+					api::configure(&api_handle);
+				}
+			});
 
 			#[cfg(desktop)]
 			{
-				use tauri_plugin_global_shortcut::{
-					Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState,
-				};
+				use tauri_plugin_global_shortcut::ShortcutState;
 
 				// Initialize the panel on macOS
 				#[cfg(target_os = "macos")]
 				{
 					macos::init_panel(app.handle());
 				}
+				// Hide on focus loss on Linux, and explain the keybinding fallback under Wayland
+				#[cfg(target_os = "linux")]
+				linux::init_panel(app.handle());
 
-				// Option+Space on macOS, Alt+Space on Windows/Linux
-				let shortcut = Shortcut::new(Some(Modifiers::ALT), Code::Space);
-				let app_handle = app.handle().clone();
-
+				app.manage(hotkeys::HotkeyState::default());
 				app.handle().plugin(
 					tauri_plugin_global_shortcut::Builder::new()
-						.with_handler(move |_app, hotkey, event| {
-							if hotkey == &shortcut && event.state() == ShortcutState::Pressed {
-								#[cfg(target_os = "macos")]
-								{
-									if macos::is_panel_visible(&app_handle) {
-										macos::hide_panel(&app_handle);
-									} else {
-										// Center the window before showing
-										if let Some(window) =
-											app_handle.get_webview_window("spotlight")
-										{
-											let _ = window.center();
-										}
-										macos::show_panel(&app_handle);
-									}
-								}
-
-								#[cfg(not(target_os = "macos"))]
-								{
-									if let Some(window) = app_handle.get_webview_window("spotlight")
-									{
-										if window.is_visible().unwrap_or(false) {
-											let _ = window.hide();
-										} else {
-											let _ = window.center();
-											let _ = window.show();
-											let _ = window.set_focus();
-										}
-									}
-								}
+						.with_handler(move |app, hotkey, event| {
+							if event.state() == ShortcutState::Pressed {
+								hotkeys::handle(app, hotkey);
 							}
 						})
 						.build(),
 				)?;
 
-				app.global_shortcut().register(shortcut)?;
+				// A bad binding in settings shouldn't keep the app from starting; the default shortcut only stands in
+				// when none of the configured ones could be registered
+				let bindings = hotkeys::configured_bindings(app.handle());
+				if let Err(e) = hotkeys::apply(app.handle(), &bindings) {
+					log::error!("[hotkeys] {}", e);
+				}
+				if !hotkeys::has_registered(app.handle()) {
+					if let Err(e) = hotkeys::apply(app.handle(), &hotkeys::default_bindings()) {
+						log::error!("[hotkeys] Default shortcut unavailable: {}", e);
+					}
+				}
+
+				// Pick up bindings saved by the Options window, unless paused from the tray
+				let hotkeys_handle = app.handle().clone();
+				app.listen_any("settings://updated", move |event| {
+					if !event.payload().contains("\"hotkeys\"")
+						|| hotkeys::is_paused(&hotkeys_handle)
+					{
+						return;
+					}
+					let bindings = hotkeys::configured_bindings(&hotkeys_handle);
+					if let Err(e) = hotkeys::apply(&hotkeys_handle, &bindings) {
+						log::error!("[hotkeys] {}", e);
+					}
+				});
 			}
 			Ok(())
 		})
@@ -1080,7 +1213,34 @@ pub fn run() {
 		show_toast,
 		translate_text,
 		cancel_translate_text,
-		log_settings_update
+		get_settings,
+		update_settings,
+		export_settings,
+		preview_settings_import,
+		import_settings,
+		get_profiles,
+		switch_profile,
+		open_pending_requests,
+		get_local_api_status,
+		copy_local_api_token,
+		regenerate_local_api_token,
+		reindex_local_docs,
+		search_local_docs,
+		list_history,
+		semantic_search,
+		pull_model,
+		cancel_pull_model,
+		delete_model,
+		show_model,
+		get_model_profile,
+		get_ollama_health,
+		ask_about_clipboard,
+		copy_last_result,
+		replace_selection,
+		#[cfg(desktop)]
+		get_hotkeys,
+		#[cfg(desktop)]
+		update_hotkeys
 	])
 		.run(tauri::generate_context!())
 		.expect("error while running tauri application");
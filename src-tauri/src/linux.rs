@@ -0,0 +1,83 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, PhysicalPosition, WebviewWindow, WindowEvent};
+
+// Panel behavior on Linux. Under Wayland an app can't grab global shortcuts, read the pointer or place its own
+// windows, so the panel is toggled by a desktop keybinding running `ai-spotlight-panel --toggle` (forwarded to the
+// running instance) and placed by the compositor. Under X11 it is centered on the monitor under the pointer.
+
+// Window managers may report a focus change while the panel is being mapped; losing focus this soon after showing
+// doesn't count as clicking outside
+const FOCUS_GRACE: Duration = Duration::from_millis(300);
+
+static SHOWN_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+// A Wayland session, where global shortcuts only reach the app while one of its windows has focus
+pub fn is_wayland_session() -> bool {
+	std::env::var_os("WAYLAND_DISPLAY").is_some()
+		|| std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session == "wayland")
+}
+
+// Whether the windows themselves are Wayland surfaces; with `GDK_BACKEND=x11` they go through XWayland and can be
+// positioned like on X11
+fn is_wayland_backend() -> bool {
+	is_wayland_session() && std::env::var("GDK_BACKEND").map_or(true, |backend| backend != "x11")
+}
+
+// Hide the panel when it loses focus, as the macOS panel does when it resigns key
+pub fn init_panel(app: &AppHandle) {
+	let Some(window) = app.get_webview_window("spotlight") else {
+		return;
+	};
+	let handle = app.clone();
+	window.on_window_event(move |event| {
+		if let WindowEvent::Focused(false) = event {
+			let just_shown = SHOWN_AT
+				.lock()
+				.expect("panel mutex poisoned")
+				.is_some_and(|shown_at| shown_at.elapsed() < FOCUS_GRACE);
+			if !just_shown && crate::is_spotlight_visible(&handle) {
+				crate::hide_spotlight(&handle);
+			}
+		}
+	});
+
+	if is_wayland_session() {
+		log::warn!(
+			"[linux] Wayland session: global shortcuts only work while the panel has focus. Bind \
+			 `ai-spotlight-panel --toggle` to a key in your desktop's keyboard settings instead."
+		);
+	}
+}
+
+// Place and show the panel. A window that is mapped again gets focus from most compositors, including under
+// Wayland where focusing an existing window from the background is refused.
+pub fn show_panel(app: &AppHandle, window: &WebviewWindow) {
+	position_panel(app, window);
+	*SHOWN_AT.lock().expect("panel mutex poisoned") = Some(Instant::now());
+	let _ = window.show();
+	let _ = window.set_focus();
+}
+
+// Center the panel on the monitor under the pointer, which is where the user is working
+fn position_panel(app: &AppHandle, window: &WebviewWindow) {
+	if is_wayland_backend() {
+		// Ignored by the compositor, which opens new windows on the active output
+		let _ = window.center();
+		return;
+	}
+
+	let monitor = app
+		.cursor_position()
+		.ok()
+		.and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten());
+	let (Some(monitor), Ok(size)) = (monitor, window.outer_size()) else {
+		let _ = window.center();
+		return;
+	};
+	let monitor_pos = monitor.position();
+	let monitor_size = monitor.size();
+	let x = monitor_pos.x + (monitor_size.width as i32 - size.width as i32) / 2;
+	let y = monitor_pos.y + (monitor_size.height as i32 - size.height as i32) / 2;
+	let _ = window.set_position(tauri::Position::Physical(PhysicalPosition { x, y }));
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+use crate::settings::{self, SettingsSource};
+use crate::vault;
+
+const DEFAULT_PAYLOAD_LIMIT: usize = 2000;
+const REDACTED: &str = "[redacted]";
+// Shorter words are never treated as tokens, so ordinary text survives the scrubbing
+const MIN_TOKEN_LEN: usize = 20;
+
+// How much user content (queries, request bodies, search results) goes into the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrivacyLevel {
+	// No content, not even its size
+	Off,
+	// Sizes only
+	#[default]
+	Metadata,
+	// Content with secrets and tokens redacted, capped at `logPayloadLimit` bytes
+	Full,
+}
+
+impl PrivacyLevel {
+	fn from_u8(value: u8) -> Self {
+		match value {
+			0 => PrivacyLevel::Off,
+			2 => PrivacyLevel::Full,
+			_ => PrivacyLevel::Metadata,
+		}
+	}
+
+	fn as_u8(self) -> u8 {
+		match self {
+			PrivacyLevel::Off => 0,
+			PrivacyLevel::Metadata => 1,
+			PrivacyLevel::Full => 2,
+		}
+	}
+}
+
+// Cached from settings so request paths without an AppHandle can log through the same rules
+static LEVEL: AtomicU8 = AtomicU8::new(1);
+static PAYLOAD_LIMIT: AtomicUsize = AtomicUsize::new(DEFAULT_PAYLOAD_LIMIT);
+
+// Read `logPrivacy` and `logPayloadLimit`; called on startup and whenever settings change
+pub fn configure(source: &impl SettingsSource) {
+	let level = settings::read::<PrivacyLevel>(source, "logPrivacy").unwrap_or_default();
+	let limit =
+		settings::read::<usize>(source, "logPayloadLimit").unwrap_or(DEFAULT_PAYLOAD_LIMIT);
+	LEVEL.store(level.as_u8(), Ordering::Relaxed);
+	PAYLOAD_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+pub fn level() -> PrivacyLevel {
+	PrivacyLevel::from_u8(LEVEL.load(Ordering::Relaxed))
+}
+
+// User-provided text (a query, a tool argument) as it may appear in the log
+pub fn text(value: &str) -> String {
+	match level() {
+		PrivacyLevel::Off => "[hidden]".to_string(),
+		PrivacyLevel::Metadata => format!("[{} chars]", value.chars().count()),
+		PrivacyLevel::Full => format!("\"{}\"", truncate(&scrub_tokens(value))),
+	}
+}
+
+// A structured payload (request body, settings update) as it may appear in the log
+pub fn payload<T: Serialize>(value: &T) -> String {
+	let Ok(value) = serde_json::to_value(value) else {
+		return "[unserializable]".to_string();
+	};
+	match level() {
+		PrivacyLevel::Off => "[hidden]".to_string(),
+		PrivacyLevel::Metadata => format!("[{} bytes]", value.to_string().len()),
+		PrivacyLevel::Full => truncate(&redact_secrets(&value).to_string()),
+	}
+}
+
+// Copy of a JSON value with every secret field replaced and token-like strings scrubbed, at any depth
+pub fn redact_secrets(value: &serde_json::Value) -> serde_json::Value {
+	match value {
+		serde_json::Value::Object(fields) => serde_json::Value::Object(
+			fields
+				.iter()
+				.map(|(key, value)| {
+					let value = if vault::is_secret_key(key) && !value.is_null() {
+						serde_json::Value::String(REDACTED.to_string())
+					} else {
+						redact_secrets(value)
+					};
+					(key.clone(), value)
+				})
+				.collect(),
+		),
+		serde_json::Value::Array(items) => {
+			serde_json::Value::Array(items.iter().map(redact_secrets).collect())
+		}
+		serde_json::Value::String(text) => serde_json::Value::String(scrub_tokens(text)),
+		other => other.clone(),
+	}
+}
+
+// Replace words that look like API keys or bearer tokens: long runs of letters, digits and `-_.` mixing letters
+// and digits. URLs and prose don't match because of their punctuation or lack of digits.
+fn scrub_tokens(text: &str) -> String {
+	let mut scrubbed = String::with_capacity(text.len());
+	let mut word = String::new();
+	let flush = |word: &mut String, scrubbed: &mut String| {
+		if looks_like_token(word) {
+			scrubbed.push_str(REDACTED);
+		} else {
+			scrubbed.push_str(word);
+		}
+		word.clear();
+	};
+	for c in text.chars() {
+		if c.is_whitespace() || matches!(c, '"' | '\'' | ',' | ';' | '=' | '(' | ')') {
+			flush(&mut word, &mut scrubbed);
+			scrubbed.push(c);
+		} else {
+			word.push(c);
+		}
+	}
+	flush(&mut word, &mut scrubbed);
+	scrubbed
+}
+
+fn looks_like_token(word: &str) -> bool {
+	word.len() >= MIN_TOKEN_LEN
+		&& word
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+		&& word.chars().any(|c| c.is_ascii_digit())
+		&& word.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+fn truncate(text: &str) -> String {
+	let limit = PAYLOAD_LIMIT.load(Ordering::Relaxed);
+	if text.len() <= limit {
+		return text.to_string();
+	}
+	let mut end = limit;
+	while !text.is_char_boundary(end) {
+		end -= 1;
+	}
+	format!("{}… [truncated, {} bytes total]", &text[..end], text.len())
+}
@@ -0,0 +1,554 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::settings::{self, SettingsSource};
+
+pub const DEFAULT_BASE_URL: &str = "http://127.0.0.1:11434";
+
+// Cached from settings (`ollamaUrl`, which profiles can override) so every request path sees the same server
+static BASE_URL: RwLock<String> = RwLock::new(String::new());
+
+// Re-read the configured server; called on startup and whenever settings change. Returns whether the server
+// changed, in which case what was learned about the old server's models no longer applies.
+pub fn configure(source: &impl SettingsSource) -> bool {
+	let url = settings::Settings::load(source).ollama_url;
+	let mut base_url = BASE_URL.write().expect("base URL lock poisoned");
+	if *base_url == url {
+		return false;
+	}
+	log::info!(
+		"[ollama] Using {}",
+		if url.is_empty() { DEFAULT_BASE_URL } else { &url }
+	);
+	*base_url = url;
+	true
+}
+
+pub fn base_url() -> String {
+	let url = BASE_URL.read().expect("base URL lock poisoned");
+	if url.is_empty() {
+		DEFAULT_BASE_URL.to_string()
+	} else {
+		url.clone()
+	}
+}
+
+// Turn a non-success response into an error that keeps Ollama's own message,
+// e.g. "model \"x\" not found, try pulling it first"
+pub async fn api_error(response: reqwest::Response) -> String {
+	let status = response.status();
+	let message = response
+		.bytes()
+		.await
+		.ok()
+		.and_then(|body| serde_json::from_slice::<serde_json::Value>(&body).ok())
+		.and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string));
+
+	match message {
+		Some(message) => format!("Ollama API error: {}: {}", status, message),
+		None => format!("Ollama API error: {}", status),
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelInfo {
+	pub name: String,
+	#[serde(default)]
+	pub size: u64,
+	#[serde(default)]
+	pub modified_at: String,
+	#[serde(default)]
+	pub digest: String,
+	#[serde(default)]
+	pub details: ModelDetailsInfo,
+	// Only known when listing with capabilities, which costs one /api/show call per model
+	#[serde(default)]
+	pub supports_tools: Option<bool>,
+	#[serde(default)]
+	pub supports_thinking: Option<bool>,
+	#[serde(default)]
+	pub context_length: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+	models: Vec<ModelInfo>,
+}
+
+pub async fn list_models(include_capabilities: bool) -> Result<Vec<ModelInfo>, String> {
+	let client = reqwest::Client::new();
+	let response = client
+		.get(format!("{}/api/tags", base_url()))
+		.send()
+		.await
+		.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
+
+	if !response.status().is_success() {
+		return Err(api_error(response).await);
+	}
+
+	// Read response body as bytes and parse JSON manually
+	let body_bytes = response
+		.bytes()
+		.await
+		.map_err(|e| format!("Failed to read response body: {}", e))?;
+
+	let mut models = serde_json::from_slice::<ModelsResponse>(&body_bytes)
+		.map_err(|e| format!("Failed to parse models response: {}", e))?
+		.models;
+
+	if include_capabilities {
+		let details =
+			futures_util::future::join_all(models.iter().map(|model| show_model(&model.name)))
+				.await;
+		for (model, details) in models.iter_mut().zip(details) {
+			match details {
+				Ok(details) => {
+					model.supports_tools = Some(details.supports_tools);
+					model.supports_thinking = Some(details.supports_thinking);
+					model.context_length = details.context_length;
+				}
+				Err(e) => log::warn!("[list_models] Failed to show {}: {}", model.name, e),
+			}
+		}
+	}
+
+	Ok(models)
+}
+
+#[derive(Debug, Serialize)]
+struct ModelRequest<'a> {
+	model: &'a str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	stream: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullResponseLine {
+	#[serde(default)]
+	status: String,
+	digest: Option<String>,
+	total: Option<u64>,
+	completed: Option<u64>,
+	error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PullProgress {
+	pub model: String,
+	pub status: String,
+	pub digest: Option<String>,
+	pub total: Option<u64>,
+	pub completed: Option<u64>,
+}
+
+// Stream /api/pull, forwarding each progress line as an `ollama://pull-progress` event
+pub async fn pull_model(app: &AppHandle, model: &str) -> Result<(), String> {
+	use futures_util::StreamExt;
+
+	let json_body = serde_json::to_string(&ModelRequest {
+		model,
+		stream: Some(true),
+	})
+	.map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+	let client = reqwest::Client::new();
+	let response = client
+		.post(format!("{}/api/pull", base_url()))
+		.header("Content-Type", "application/json")
+		.body(json_body)
+		.send()
+		.await
+		.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
+
+	if !response.status().is_success() {
+		return Err(api_error(response).await);
+	}
+
+	let mut stream = response.bytes_stream();
+	let mut buffer = Vec::new();
+	let mut succeeded = false;
+
+	while let Some(chunk) = stream.next().await {
+		let bytes = chunk.map_err(|e| format!("Stream error: {}", e))?;
+		buffer.extend_from_slice(&bytes);
+
+		while let Some(newline_pos) = buffer.iter().position(|&b| b == b'\n') {
+			let line: Vec<u8> = buffer.drain(..=newline_pos).collect();
+			if line.len() <= 1 {
+				continue;
+			}
+
+			let progress: PullResponseLine = match serde_json::from_slice(&line) {
+				Ok(progress) => progress,
+				Err(e) => {
+					log::warn!("[pull_model] Failed to parse progress line: {}", e);
+					continue;
+				}
+			};
+			if let Some(error) = progress.error {
+				return Err(format!("Failed to pull {}: {}", model, error));
+			}
+			succeeded = progress.status == "success";
+
+			let _ = app.emit(
+				"ollama://pull-progress",
+				PullProgress {
+					model: model.to_string(),
+					status: progress.status,
+					digest: progress.digest,
+					total: progress.total,
+					completed: progress.completed,
+				},
+			);
+		}
+	}
+
+	if succeeded {
+		Ok(())
+	} else {
+		Err(format!("Pull of {} ended before completing", model))
+	}
+}
+
+pub async fn delete_model(model: &str) -> Result<(), String> {
+	let json_body = serde_json::to_string(&ModelRequest {
+		model,
+		stream: None,
+	})
+	.map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+	let client = reqwest::Client::new();
+	let response = client
+		.delete(format!("{}/api/delete", base_url()))
+		.header("Content-Type", "application/json")
+		.body(json_body)
+		.send()
+		.await
+		.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
+
+	if !response.status().is_success() {
+		return Err(api_error(response).await);
+	}
+	Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct PreloadRequest<'a> {
+	model: &'a str,
+	messages: [(); 0],
+	#[serde(skip_serializing_if = "Option::is_none")]
+	options: Option<GenerationOptions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	keep_alive: Option<KeepAlive>,
+}
+
+// Load a model into memory without generating anything; Ollama does that for a chat with no messages. `options` has
+// to match the queries', since a different `num_ctx` makes Ollama load the model again.
+pub async fn preload_model(
+	model: &str,
+	options: Option<GenerationOptions>,
+	keep_alive: Option<KeepAlive>,
+) -> Result<(), String> {
+	let json_body = serde_json::to_string(&PreloadRequest {
+		model,
+		messages: [],
+		options,
+		keep_alive,
+	})
+	.map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+	let client = reqwest::Client::new();
+	let response = client
+		.post(format!("{}/api/chat", base_url()))
+		.header("Content-Type", "application/json")
+		.body(json_body)
+		.send()
+		.await
+		.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
+
+	if !response.status().is_success() {
+		return Err(api_error(response).await);
+	}
+	Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModelDetailsInfo {
+	#[serde(default)]
+	pub format: String,
+	#[serde(default)]
+	pub family: String,
+	#[serde(default)]
+	pub families: Option<Vec<String>>,
+	#[serde(default)]
+	pub parameter_size: String,
+	#[serde(default)]
+	pub quantization_level: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShowResponse {
+	#[serde(default)]
+	details: ModelDetailsInfo,
+	#[serde(default)]
+	model_info: serde_json::Map<String, serde_json::Value>,
+	#[serde(default)]
+	capabilities: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ModelDetails {
+	pub name: String,
+	pub family: String,
+	pub parameter_size: String,
+	pub quantization_level: String,
+	pub context_length: Option<u64>,
+	pub capabilities: Vec<String>,
+	pub supports_tools: bool,
+	pub supports_thinking: bool,
+}
+
+// Fetch /api/show and flatten the parts the app cares about
+pub async fn show_model(model: &str) -> Result<ModelDetails, String> {
+	let json_body = serde_json::to_string(&ModelRequest {
+		model,
+		stream: None,
+	})
+	.map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+	let client = reqwest::Client::new();
+	let response = client
+		.post(format!("{}/api/show", base_url()))
+		.header("Content-Type", "application/json")
+		.body(json_body)
+		.send()
+		.await
+		.map_err(|e| format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e))?;
+
+	if !response.status().is_success() {
+		return Err(api_error(response).await);
+	}
+
+	let body_bytes = response
+		.bytes()
+		.await
+		.map_err(|e| format!("Failed to read response body: {}", e))?;
+	let show: ShowResponse = serde_json::from_slice(&body_bytes)
+		.map_err(|e| format!("Failed to parse show response: {}", e))?;
+
+	// Context length is reported under an architecture-specific key such as "qwen3.context_length"
+	let context_length = show
+		.model_info
+		.iter()
+		.find(|(key, _)| key.ends_with(".context_length"))
+		.and_then(|(_, value)| value.as_u64());
+
+	Ok(ModelDetails {
+		name: model.to_string(),
+		family: show.details.family,
+		parameter_size: show.details.parameter_size,
+		quantization_level: show.details.quantization_level,
+		context_length,
+		supports_tools: show.capabilities.iter().any(|c| c == "tools"),
+		supports_thinking: show.capabilities.iter().any(|c| c == "thinking"),
+		capabilities: show.capabilities,
+	})
+}
+
+// Sampling and context settings sent as Ollama's `options`; unset fields keep the model's defaults.
+// Settings use camelCase like the rest of `settings.json`, Ollama expects snake_case.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all(serialize = "snake_case", deserialize = "camelCase"))]
+pub struct GenerationOptions {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub temperature: Option<f32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub top_p: Option<f32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub num_ctx: Option<u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub num_predict: Option<i32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub seed: Option<i64>,
+}
+
+impl GenerationOptions {
+	// Fields set in `overrides` win, the rest come from `self`
+	pub fn merged_with(self, overrides: &GenerationOptions) -> Self {
+		GenerationOptions {
+			temperature: overrides.temperature.or(self.temperature),
+			top_p: overrides.top_p.or(self.top_p),
+			num_ctx: overrides.num_ctx.or(self.num_ctx),
+			num_predict: overrides.num_predict.or(self.num_predict),
+			seed: overrides.seed.or(self.seed),
+		}
+	}
+
+	// None when nothing is set, so requests omit `options` entirely
+	pub fn non_empty(self) -> Option<Self> {
+		if self == GenerationOptions::default() {
+			None
+		} else {
+			Some(self)
+		}
+	}
+}
+
+// Options from the `generationOptions` setting
+pub fn configured_options(source: &impl SettingsSource) -> GenerationOptions {
+	settings::read(source, "generationOptions").unwrap_or_default()
+}
+
+// How long Ollama keeps the model loaded after a request: seconds (negative keeps it forever) or a duration like "30m"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeepAlive {
+	Seconds(i64),
+	Duration(String),
+}
+
+// The `keepAlive` setting; unset leaves Ollama's five minute default
+pub fn configured_keep_alive(source: &impl SettingsSource) -> Option<KeepAlive> {
+	settings::read::<KeepAlive>(source, "keepAlive")
+		.filter(|keep_alive| !matches!(keep_alive, KeepAlive::Duration(d) if d.trim().is_empty()))
+}
+
+// How a chat request should be shaped for a given model
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelProfile {
+	// Send the `tools` array; models without tool support reject or ignore it
+	pub tools: bool,
+	// Send the `think` flag; non-thinking models fail with "does not support thinking"
+	pub think: bool,
+	// Append Qwen-style ` /think` / ` /no_think` to the user message
+	pub prompt_suffixes: bool,
+}
+
+impl ModelProfile {
+	// Used when detection fails: plain chat, which every model accepts
+	const FALLBACK: ModelProfile = ModelProfile {
+		tools: false,
+		think: false,
+		prompt_suffixes: false,
+	};
+
+	fn from_details(details: &ModelDetails) -> Self {
+		ModelProfile {
+			tools: details.supports_tools,
+			think: details.supports_thinking,
+			prompt_suffixes: details.family.to_lowercase().starts_with("qwen3"),
+		}
+	}
+}
+
+// Per-model overrides from the `modelProfiles` setting; unset fields keep the detected value
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelProfileOverride {
+	tools: Option<bool>,
+	think: Option<bool>,
+	prompt_suffixes: Option<bool>,
+}
+
+// How long a failed detection is remembered before /api/show is asked again
+const DETECTION_RETRY_DELAY: Duration = Duration::from_secs(120);
+
+// Profiles detected from /api/show, cached so each query doesn't pay for an extra request
+#[derive(Default)]
+pub struct ModelProfiles {
+	detected: Mutex<HashMap<String, ModelProfile>>,
+	// When detection last failed per model; those models get the fallback until the retry delay has passed
+	failed: Mutex<HashMap<String, Instant>>,
+}
+
+impl ModelProfiles {
+	pub async fn resolve(&self, source: &impl SettingsSource, model: &str) -> ModelProfile {
+		let cached = self
+			.detected
+			.lock()
+			.expect("model profiles mutex poisoned")
+			.get(model)
+			.copied();
+
+		let failed_recently = self
+			.failed
+			.lock()
+			.expect("model profiles mutex poisoned")
+			.get(model)
+			.is_some_and(|failed_at| failed_at.elapsed() < DETECTION_RETRY_DELAY);
+
+		let detected = match cached {
+			Some(profile) => profile,
+			None if failed_recently => ModelProfile::FALLBACK,
+			None => match show_model(model).await {
+				Ok(details) => {
+					let profile = ModelProfile::from_details(&details);
+					log::info!("[model_profile] Detected {} -> {:?}", model, profile);
+					self.failed
+						.lock()
+						.expect("model profiles mutex poisoned")
+						.remove(model);
+					self.detected
+						.lock()
+						.expect("model profiles mutex poisoned")
+						.insert(model.to_string(), profile);
+					profile
+				}
+				Err(e) => {
+					log::warn!(
+						"[model_profile] Detection failed for {}, using plain chat for now: {}",
+						model,
+						e
+					);
+					self.failed
+						.lock()
+						.expect("model profiles mutex poisoned")
+						.insert(model.to_string(), Instant::now());
+					ModelProfile::FALLBACK
+				}
+			},
+		};
+
+		let overrides: HashMap<String, ModelProfileOverride> =
+			settings::read(source, "modelProfiles").unwrap_or_default();
+		match overrides.get(model) {
+			Some(profile_override) => ModelProfile {
+				tools: profile_override.tools.unwrap_or(detected.tools),
+				think: profile_override.think.unwrap_or(detected.think),
+				prompt_suffixes: profile_override
+					.prompt_suffixes
+					.unwrap_or(detected.prompt_suffixes),
+			},
+			None => detected,
+		}
+	}
+
+	// Forget a detected profile, e.g. after the model was re-pulled or deleted
+	pub fn invalidate(&self, model: &str) {
+		self.detected
+			.lock()
+			.expect("model profiles mutex poisoned")
+			.remove(model);
+		self.failed
+			.lock()
+			.expect("model profiles mutex poisoned")
+			.remove(model);
+	}
+
+	// Forget every detected profile, e.g. after switching to another server
+	pub fn clear(&self) {
+		self.detected
+			.lock()
+			.expect("model profiles mutex poisoned")
+			.clear();
+		self.failed
+			.lock()
+			.expect("model profiles mutex poisoned")
+			.clear();
+	}
+}
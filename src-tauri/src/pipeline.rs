@@ -0,0 +1,531 @@
+use serde::{Deserialize, Serialize};
+
+use crate::knowledge::{self, KnowledgeBase};
+use crate::ollama::{self, ModelProfiles};
+use crate::settings::{self, SettingsSource};
+use crate::{logging, prompts, templates};
+use crate::{ChatRequestWithTools, ChatResponse, Tool, ToolFunction};
+
+// The quick answer and translation pipelines, independent of windows and managed state so the app's commands and
+// the CLI run the same code. Callers handle cancellation, history and UI updates.
+
+// A finished quick answer and the model that produced it, which a template may have chosen
+#[derive(Debug, Serialize)]
+pub struct Answer {
+	pub text: String,
+	pub model: String,
+}
+
+// Web search tool definition
+fn get_web_search_tool() -> Tool {
+	Tool {
+		tool_type: "function".to_string(),
+		function: ToolFunction {
+			name: "web_search".to_string(),
+			description: "Search the internet for current information. Use this when you need to find up-to-date information or facts you don't know.".to_string(),
+			parameters: serde_json::json!({
+				"type": "object",
+				"required": ["query"],
+				"properties": {
+					"query": {
+						"type": "string",
+						"description": "The search query to look up on the internet"
+					}
+				}
+			}),
+		},
+	}
+}
+
+// Execute web search using the configured search API
+async fn execute_web_search(
+	query: &str,
+	api_url: &str,
+	api_key: &str,
+) -> Result<String, String> {
+	let api_url = api_url.trim();
+	log::info!(
+		"[quick_answer] execute_web_search received url_len={}, has_key={}",
+		api_url.len(),
+		!api_key.trim().is_empty()
+	);
+	if api_url.is_empty() {
+		return Err("Search API URL not configured in Options".to_string());
+	}
+	let api_key = api_key.trim();
+	if api_key.is_empty() {
+		return Err("Search API key not configured in Options".to_string());
+	}
+
+	let client = reqwest::Client::new();
+	let response = client
+		.post(format!("{}?format=json", api_url))
+		.header("Authorization", format!("Bearer {}", api_key))
+		.header("Content-Type", "application/x-www-form-urlencoded")
+		.body(format!("q={}", urlencoding::encode(query)))
+		.send()
+		.await
+		.map_err(|e| format!("Search request failed: {}", e))?;
+
+	if !response.status().is_success() {
+		return Err(format!("Search API error: {}", response.status()));
+	}
+
+	response
+		.text()
+		.await
+		.map_err(|e| format!("Failed to read search response: {}", e))
+}
+
+// Answer a query with the configured model, calling web search and local docs tools as needed
+pub async fn quick_answer(
+	source: &impl SettingsSource,
+	knowledge_base: &KnowledgeBase,
+	model_profiles: &ModelProfiles,
+	text: String,
+) -> Result<Answer, String> {
+	let settings = settings::Settings::load(source);
+
+	// A leading prefix such as "/fix" routes the query to a user-defined template
+	let (template, text) = match templates::match_prefix(&templates::configured(source), &text) {
+		Some((template, rest)) => (Some(template), rest),
+		None => (None, text),
+	};
+	let model = template
+		.as_ref()
+		.and_then(|template| template.model.clone())
+		.filter(|model| !model.trim().is_empty())
+		.unwrap_or(settings.ollama_model);
+	let enable_thinking = template
+		.as_ref()
+		.and_then(|template| template.thinking)
+		.unwrap_or(settings.enable_thinking);
+	let options = ollama::configured_options(source)
+		.merged_with(&template.as_ref().and_then(|template| template.options).unwrap_or_default())
+		.non_empty();
+	let keep_alive = ollama::configured_keep_alive(source);
+	let search_api_url = settings.web_search_api_url;
+	let search_api_key = settings.web_search_api_key;
+	let answer_model = model.clone();
+
+	let answer = async {
+		log::info!(
+			"[quick_answer] Called with model={}, enable_thinking={}, template={:?}",
+			model,
+			enable_thinking,
+			template.as_ref().map(|template| template.prefix.as_str())
+		);
+
+		if text.trim().is_empty() {
+			log::warn!("[quick_answer] Empty text provided");
+			return Err("Empty text".to_string());
+		}
+		if model.is_empty() {
+			return Err("No Ollama model selected in Options".to_string());
+		}
+
+		log::info!(
+			"[quick_answer] Loaded web search settings url_len={}, has_key={}",
+			search_api_url.trim().len(),
+			!search_api_key.trim().is_empty()
+		);
+
+		let client = reqwest::Client::new();
+		let profile = model_profiles.resolve(source, &model).await;
+		log::info!("[quick_answer] Using model profile {:?}", profile);
+
+		let has_local_docs = knowledge_base.has_documents();
+		// Without a search URL (e.g. a profile with search turned off) the model isn't offered web_search
+		let mut tools = Vec::new();
+		if !search_api_url.trim().is_empty() {
+			tools.push(get_web_search_tool());
+		}
+		if has_local_docs {
+			tools.push(knowledge::search_tool());
+		}
+		let system_prompt = match &template {
+			Some(template) => {
+				tools.retain(|tool| template.allows_tool(&tool.function.name));
+				template.system_prompt.clone()
+			}
+			None if profile.tools && !tools.is_empty() => {
+				// With only local docs to search, the web search prompt would ask for a tool the model doesn't have
+				let mode = if tools.iter().any(|tool| tool.function.name == "web_search") {
+					prompts::PromptMode::WebSearch
+				} else {
+					prompts::PromptMode::NoTools
+				};
+				let mut system_prompt = prompts::system_prompt(source, mode);
+				if has_local_docs {
+					system_prompt.push_str(prompts::LOCAL_DOCS_PROMPT_ADDENDUM);
+				}
+				system_prompt
+			}
+			None => prompts::system_prompt(source, prompts::PromptMode::NoTools),
+		};
+		let mut system_prompt =
+			prompts::render(&system_prompt, &prompts::PromptVars::current(source));
+		if let Some(context) = prompts::context_block(source) {
+			system_prompt.push_str(&context);
+		}
+		let tools = if profile.tools && !tools.is_empty() {
+			Some(tools)
+		} else {
+			None
+		};
+		let think = if profile.think { Some(enable_thinking) } else { None };
+
+		// Build initial messages
+		// For Qwen3 and similar models, add /no_think or /think suffix to control thinking mode
+		let user_content = if profile.prompt_suffixes {
+			let thinking_suffix = if enable_thinking { " /think" } else { " /no_think" };
+			format!("{}{}", text, thinking_suffix)
+		} else {
+			text.clone()
+		};
+
+		let system_msg = serde_json::json!({
+			"role": "system",
+			"content": system_prompt
+		});
+		let user_msg = serde_json::json!({
+			"role": "user",
+			"content": user_content
+		});
+		let mut messages = vec![system_msg, user_msg];
+
+		// First request with tools
+		let request_body = ChatRequestWithTools {
+			model: model.clone(),
+			messages: serde_json::Value::Array(messages.clone()),
+			stream: false,
+			tools: tools.clone(),
+			think,
+			options,
+			keep_alive: keep_alive.clone(),
+		};
+
+		let json_body = serde_json::to_string(&request_body)
+			.map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+		log::info!("[quick_answer] Sending request to Ollama with think={:?}", think);
+		log::info!("[quick_answer] Request body: {}", logging::payload(&request_body));
+
+		let response = client
+			.post(format!("{}/api/chat", ollama::base_url()))
+			.header("Content-Type", "application/json")
+			.body(json_body)
+			.send()
+			.await
+			.map_err(|e| {
+				format!(
+					"Failed to connect to Ollama: {}. Make sure Ollama is running.",
+					e
+				)
+			})?;
+
+		if !response.status().is_success() {
+			return Err(ollama::api_error(response).await);
+		}
+
+		let body_bytes = response
+			.bytes()
+			.await
+			.map_err(|e| format!("Failed to read response: {}", e))?;
+
+		let chat_response: ChatResponse = serde_json::from_slice(&body_bytes)
+			.map_err(|e| format!("Failed to parse response: {}", e))?;
+
+		// Check if the model wants to call tools
+		if let Some(ref message) = chat_response.message {
+			if let Some(ref tool_calls) = message.tool_calls {
+				if !tool_calls.is_empty() {
+					// Process tool calls
+					let mut tool_results = Vec::new();
+
+					for tool_call in tool_calls {
+						// Extract the query from arguments
+						let query = tool_call
+							.function
+							.arguments
+							.get("query")
+							.and_then(|v| v.as_str())
+							.unwrap_or("");
+
+						if query.is_empty() {
+							continue;
+						}
+
+						match tool_call.function.name.as_str() {
+							"web_search" => {
+								log::info!(
+									"[quick_answer] Executing web_search with query={}",
+									logging::text(query)
+								);
+								match execute_web_search(query, &search_api_url, &search_api_key)
+									.await
+								{
+									Ok(result) => {
+										tool_results
+											.push((tool_call.function.name.clone(), result));
+									}
+									Err(e) => {
+										log::warn!("[quick_answer] web_search failed: {}", e);
+										tool_results.push((
+											tool_call.function.name.clone(),
+											format!("Search failed: {}", e),
+										));
+									}
+								}
+							}
+							knowledge::SEARCH_TOOL_NAME => {
+								log::info!(
+									"[quick_answer] Executing search_local_docs with query={}",
+									logging::text(query)
+								);
+								let result = knowledge_base.search_for_tool(query).await;
+								tool_results.push((tool_call.function.name.clone(), result));
+							}
+							_ => {}
+						}
+					}
+
+					// Add assistant message with tool calls to conversation
+					let assistant_msg = serde_json::json!({
+						"role": "assistant",
+						"content": message.content.clone(),
+						"tool_calls": message.tool_calls
+					});
+					messages.push(assistant_msg);
+
+					// Add tool results to conversation
+					for (tool_name, result) in tool_results {
+						let tool_msg = serde_json::json!({
+							"role": "tool",
+							"tool_name": tool_name,
+							"content": result
+						});
+						messages.push(tool_msg);
+					}
+
+					// Make second request with tool results
+					let follow_up_request = ChatRequestWithTools {
+						model: model.clone(),
+						messages: serde_json::Value::Array(messages),
+						stream: false,
+						tools,
+						think,
+						options,
+						keep_alive,
+					};
+
+					let json_body = serde_json::to_string(&follow_up_request)
+						.map_err(|e| format!("Failed to serialize follow-up request: {}", e))?;
+
+					let response = client
+						.post(format!("{}/api/chat", ollama::base_url()))
+						.header("Content-Type", "application/json")
+						.body(json_body)
+						.send()
+						.await
+						.map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
+
+					if !response.status().is_success() {
+						return Err(ollama::api_error(response).await);
+					}
+
+					let body_bytes = response
+						.bytes()
+						.await
+						.map_err(|e| format!("Failed to read follow-up response: {}", e))?;
+
+					let final_response: ChatResponse = serde_json::from_slice(&body_bytes)
+						.map_err(|e| format!("Failed to parse follow-up response: {}", e))?;
+
+					return final_response
+						.message
+						.map(|m| m.content)
+						.ok_or_else(|| "No response from model".to_string());
+				}
+			}
+		}
+		// No tool calls, return direct response
+		chat_response
+			.message
+			.map(|m| m.content)
+			.ok_or_else(|| "No response from model".to_string())
+	}
+	.await?;
+
+	Ok(Answer {
+		text: answer,
+		model: answer_model,
+	})
+}
+
+// Translation result structure
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranslationResult {
+	pub text: String,
+	pub detected_language: String,
+}
+
+async fn translate_with_target(
+	client: &reqwest::Client,
+	text: &str,
+	target_language: &str,
+) -> Result<TranslationResult, String> {
+	let target_language = target_language.trim();
+
+	// Use the batch translate endpoint (more reliable, less rate-limited)
+	let rpcids = "MkEWBc";
+	let req_id: u32 = rand::random::<u32>() % 9000 + 1000;
+
+	let query_params = format!(
+		"rpcids={}&source-path=/&f.sid=&bl=&hl=en-US&soc-app=1&soc-platform=1&soc-device=1&_reqid={}&rt=c",
+		rpcids, req_id
+	);
+
+	let url = format!(
+		"https://translate.google.com/_/TranslateWebserverUi/data/batchexecute?{}",
+		query_params
+	);
+
+	// Build the request body
+	// Format: [[["MkEWBc","[[\"text\",\"auto\",\"en\",true],[null]]",null,"1"]]]
+	let freq_inner = serde_json::json!([[text, "auto", target_language, true], [null]]);
+	let freq = serde_json::json!([[[rpcids, freq_inner.to_string(), null, "0"]]]);
+	let body = format!("f.req={}&", urlencoding::encode(&freq.to_string()));
+
+	let response = client
+		.post(&url)
+		.header("Content-Type", "application/x-www-form-urlencoded;charset=UTF-8")
+		.body(body)
+		.send()
+		.await
+		.map_err(|e| format!("Request failed: {}", e))?;
+
+	if !response.status().is_success() {
+		return Err(format!("HTTP error: {}", response.status()));
+	}
+
+	let response_text = response
+		.text()
+		.await
+		.map_err(|e| format!("Failed to read response: {}", e))?;
+
+	// Parse the response - it's in a special format
+	// Skip the first 6 characters (")]}'\n\n")
+	let json_text = if response_text.len() > 6 {
+		&response_text[6..]
+	} else {
+		return Err("Invalid response format".to_string());
+	};
+
+	// Find the first valid JSON array line
+	for line in json_text.lines() {
+		if !line.starts_with('[') || line.contains("\"e\"") {
+			continue;
+		}
+
+		// Parse the outer array
+		let outer: serde_json::Value = serde_json::from_str(line)
+			.map_err(|e| format!("Failed to parse response JSON: {}", e))?;
+
+		// Navigate the nested structure to find the translation
+		// Structure: [[["wrb.fr", "MkEWBc", "[[...translation data...]]", ...]]]
+		if let Some(arr) = outer.as_array() {
+			for item in arr {
+				if let Some(inner_arr) = item.as_array() {
+					if inner_arr.len() >= 3 {
+						if let Some(wrapper_type) = inner_arr.first().and_then(|v| v.as_str()) {
+							if wrapper_type == "wrb.fr" {
+								if let Some(data_str) = inner_arr.get(2).and_then(|v| v.as_str()) {
+									// Parse the inner JSON string
+									let data: serde_json::Value = serde_json::from_str(data_str)
+										.map_err(|e| format!("Failed to parse translation data: {}", e))?;
+
+									// Extract translation and detected language
+									// Structure: [[[null, null, null, [[[0, [[[null, "translated text"]]]...], detected_lang, ...
+									if let Some(translation_data) = data.get(1).and_then(|v| v.get(0)).and_then(|v| v.get(0)).and_then(|v| v.get(5)) {
+										if let Some(parts) = translation_data.as_array() {
+											let mut translated_text = String::new();
+											for part in parts {
+												if let Some(segment) = part.get(0).and_then(|v| v.as_str()) {
+													translated_text.push_str(segment);
+												}
+											}
+
+											// Get detected language
+											let detected_lang = data
+												.get(1)
+												.and_then(|v| v.get(3))
+												.and_then(|v| v.as_str())
+												.or_else(|| data.get(2).and_then(|v| v.as_str()))
+												.unwrap_or("auto")
+												.to_string();
+
+											return Ok(TranslationResult {
+												text: translated_text,
+												detected_language: detected_lang,
+											});
+										}
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	Err("Could not parse translation from response".to_string())
+}
+
+// Translate non-English text to English, and English text to the configured second language
+pub async fn translate(source: &impl SettingsSource, text: &str) -> Result<TranslationResult, String> {
+	if text.trim().is_empty() {
+		return Err("Empty text".to_string());
+	}
+
+	let client = reqwest::Client::new();
+	let second_language = settings::Settings::load(source).translation_second_language;
+	let trimmed_language = second_language.trim();
+
+	if trimmed_language.is_empty() || trimmed_language == "en" {
+		let english_result = translate_with_target(&client, text, "en").await?;
+		if english_result.detected_language == "en" {
+			return Err("Source is English".to_string());
+		}
+		return Ok(english_result);
+	}
+
+	let second_language_result = translate_with_target(&client, text, trimmed_language).await?;
+
+	if second_language_result.detected_language == "en" {
+		return Ok(second_language_result);
+	}
+
+	translate_with_target(&client, text, "en").await
+}
+
+// Translate text to one language; text that is already in it follows the panel's rules from `translate`
+pub async fn translate_to(
+	source: &impl SettingsSource,
+	text: &str,
+	target_language: &str,
+) -> Result<TranslationResult, String> {
+	if text.trim().is_empty() {
+		return Err("Empty text".to_string());
+	}
+	let target_language = target_language.trim();
+	settings::validate_language(target_language)?;
+
+	let result = translate_with_target(&reqwest::Client::new(), text, target_language).await?;
+	let target_base = target_language.split('-').next().unwrap_or_default();
+	if result.detected_language.eq_ignore_ascii_case(target_base) {
+		return translate(source, text).await;
+	}
+	Ok(result)
+}
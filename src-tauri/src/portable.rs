@@ -0,0 +1,436 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::{api, profiles, settings, vault};
+
+const EXPORT_FORMAT: &str = "ai-spotlight-settings";
+// Bumped when the file layout changes; the settings inside are versioned by `schemaVersion`
+const EXPORT_VERSION: u32 = 1;
+// Kept out of exports: the schema version is recorded separately, folder paths only make sense on one machine, and
+// the local API should only be opened on purpose on each machine
+const EXCLUDED_KEYS: &[&str] = &[settings::SCHEMA_VERSION_KEY, "localDocsFolders", "localApi"];
+
+// A settings file that can be shared between machines; secrets are only included sealed under a passphrase
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportFile {
+	format: String,
+	version: u32,
+	schema_version: u64,
+	exported_at: String,
+	settings: serde_json::Map<String, serde_json::Value>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	secrets: Option<vault::PassphraseSealed>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+	// Add and overwrite the file's keys, keep everything else
+	Merge,
+	// Make the settings match the file, removing keys it doesn't have
+	Replace,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+	Added,
+	Changed,
+	Removed,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+	pub key: String,
+	pub kind: ChangeKind,
+	pub current: Option<serde_json::Value>,
+	pub incoming: Option<serde_json::Value>,
+}
+
+// What an import would do (or did): the changed keys, and which secrets it carries
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+	pub schema_version: u64,
+	pub exported_at: String,
+	pub changes: Vec<SettingChange>,
+	// Names only; when skipped, the ones a passphrase would import
+	pub secrets: Vec<String>,
+	// The file has encrypted secrets but no passphrase was given, so they are left out
+	pub secrets_skipped: bool,
+}
+
+// Write the current settings to `path`; secrets are added, encrypted, only when a passphrase is given
+pub fn export(app: &AppHandle, path: &Path, passphrase: Option<&str>) -> Result<(), String> {
+	let store = app
+		.store(settings::STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	let mut exported: serde_json::Map<_, _> = store
+		.entries()
+		.into_iter()
+		.filter(|(key, _)| is_portable(key))
+		.collect();
+	// Profiles can override the machine-specific keys too
+	if let Some(profiles) = exported
+		.get_mut(settings::PROFILES_KEY)
+		.and_then(serde_json::Value::as_object_mut)
+	{
+		for overrides in profiles.values_mut().filter_map(serde_json::Value::as_object_mut) {
+			overrides.retain(|key, _| is_portable(key));
+		}
+	}
+
+	let secrets = match passphrase {
+		Some(passphrase) => {
+			let entries = app
+				.try_state::<vault::Vault>()
+				.map(|vault| vault.entries())
+				.unwrap_or_default()
+				.into_iter()
+				.filter(|(name, _)| name != api::TOKEN_NAME)
+				.collect();
+			Some(vault::seal_with_passphrase(passphrase, &entries)?)
+		}
+		None => None,
+	};
+
+	let file = ExportFile {
+		format: EXPORT_FORMAT.to_string(),
+		version: EXPORT_VERSION,
+		schema_version: settings::SCHEMA_VERSION,
+		exported_at: chrono::Local::now().to_rfc3339(),
+		settings: exported,
+		secrets,
+	};
+	let json = serde_json::to_vec_pretty(&file)
+		.map_err(|e| format!("Failed to serialize settings: {}", e))?;
+	std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+	log::info!(
+		"[portable] Exported {} settings to {}",
+		file.settings.len(),
+		path.display()
+	);
+	Ok(())
+}
+
+// Show what importing `path` would change without touching the settings
+pub fn preview(
+	app: &AppHandle,
+	path: &Path,
+	mode: ImportMode,
+	passphrase: Option<&str>,
+) -> Result<ImportPreview, String> {
+	let file = read_file(path)?;
+	let (secrets, secrets_skipped) = incoming_secrets(&file, passphrase)?;
+	let current = current_settings(app)?;
+	check_active_profile(&current, &file, mode)?;
+	let mut names: BTreeSet<String> = secrets.into_keys().collect();
+	if let Some(sealed) = file.secrets.as_ref().filter(|_| secrets_skipped) {
+		names.extend(sealed.names().map(str::to_string));
+	}
+	Ok(ImportPreview {
+		schema_version: file.schema_version,
+		exported_at: file.exported_at.clone(),
+		changes: diff(&current, &file, mode),
+		secrets: names.into_iter().collect(),
+		secrets_skipped,
+	})
+}
+
+// Apply `path` to the settings, announce the changed keys and return what changed
+pub fn import(
+	app: &AppHandle,
+	path: &Path,
+	mode: ImportMode,
+	passphrase: Option<&str>,
+) -> Result<ImportPreview, String> {
+	let file = read_file(path)?;
+	let (secrets, secrets_skipped) = incoming_secrets(&file, passphrase)?;
+	let current = current_settings(app)?;
+	check_active_profile(&current, &file, mode)?;
+	let changes = diff(&current, &file, mode);
+
+	let store = app
+		.store(settings::STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	for change in &changes {
+		match &change.incoming {
+			Some(value) => store.set(change.key.as_str(), value.clone()),
+			None => {
+				store.delete(&change.key);
+			}
+		}
+	}
+	// Files from an older schema are upgraded in place by the regular migrations
+	if file.schema_version < settings::SCHEMA_VERSION {
+		store.set(settings::SCHEMA_VERSION_KEY, file.schema_version);
+	}
+	store
+		.save()
+		.map_err(|e| format!("Failed to save settings: {}", e))?;
+	settings::migrate(app)?;
+
+	for (name, value) in &secrets {
+		app.try_state::<vault::Vault>()
+			.ok_or_else(|| "Secure storage is unavailable".to_string())?
+			.set(name, value)?;
+	}
+
+	let changed: Vec<&str> = changes
+		.iter()
+		.map(|change| change.key.as_str())
+		.chain(secrets.keys().map(String::as_str))
+		.collect();
+	if !changed.is_empty() {
+		settings::notify_updated(app, &changed);
+	}
+	log::info!(
+		"[portable] Imported {} changes and {} secrets from {} ({:?})",
+		changes.len(),
+		secrets.len(),
+		path.display(),
+		mode
+	);
+
+	Ok(ImportPreview {
+		schema_version: file.schema_version,
+		exported_at: file.exported_at,
+		changes,
+		secrets: secrets.into_keys().collect(),
+		secrets_skipped,
+	})
+}
+
+fn read_file(path: &Path) -> Result<ExportFile, String> {
+	let bytes =
+		std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+	let mut file: ExportFile = serde_json::from_slice(&bytes)
+		.map_err(|e| format!("Not a settings export: {}", e))?;
+	if file.format != EXPORT_FORMAT {
+		return Err(format!("Not a settings export: unknown format \"{}\"", file.format));
+	}
+	if file.version > EXPORT_VERSION || file.schema_version > settings::SCHEMA_VERSION {
+		return Err("This file was exported by a newer version of the app".to_string());
+	}
+	sanitize_profiles(&mut file.settings)?;
+	validate(&file.settings)?;
+	Ok(file)
+}
+
+// Keys an import may write at the top level or inside a profile
+fn is_portable(key: &str) -> bool {
+	!EXCLUDED_KEYS.contains(&key) && !vault::is_secret_key(key)
+}
+
+// Profiles get the same limits as the top level: machine-specific, reserved and secret keys are dropped, and the
+// remaining overrides must be ones `profiles::switch` accepts
+fn sanitize_profiles(values: &mut serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+	let Some(profiles) = values.get_mut(settings::PROFILES_KEY) else {
+		return Ok(());
+	};
+	let profiles = profiles
+		.as_object_mut()
+		.ok_or_else(|| "Invalid profiles: expected an object".to_string())?;
+	for (name, overrides) in profiles.iter_mut() {
+		let overrides = overrides
+			.as_object_mut()
+			.ok_or_else(|| format!("Invalid profile \"{}\": expected an object", name))?;
+		overrides.retain(|key, _| is_portable(key) && !profiles::is_reserved_key(key));
+		profiles::validate(overrides).map_err(|e| format!("Profile \"{}\": {}", name, e))?;
+	}
+	Ok(())
+}
+
+// An imported `activeProfile` has to name one of the profiles the settings will have after the import
+fn check_active_profile(
+	current: &serde_json::Map<String, serde_json::Value>,
+	file: &ExportFile,
+	mode: ImportMode,
+) -> Result<(), String> {
+	let Some(active) = file.settings.get(settings::ACTIVE_PROFILE_KEY) else {
+		return Ok(());
+	};
+	let name = active
+		.as_str()
+		.ok_or_else(|| "Invalid activeProfile: expected a profile name".to_string())?;
+	let profiles = match (file.settings.get(settings::PROFILES_KEY), mode) {
+		(Some(profiles), _) => Some(profiles),
+		(None, ImportMode::Merge) => current.get(settings::PROFILES_KEY),
+		(None, ImportMode::Replace) => None,
+	};
+	if profiles.and_then(|profiles| profiles.get(name)).is_none() {
+		return Err(format!("activeProfile \"{}\" is not one of the profiles", name));
+	}
+	Ok(())
+}
+
+// Reject values the backend would refuse or silently ignore after import
+fn validate(values: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+	settings::validate_values(values)?;
+	#[cfg(desktop)]
+	if let Some(value) = values.get("hotkeys") {
+		let bindings = serde_json::from_value::<Vec<crate::hotkeys::HotkeyBinding>>(value.clone())
+			.map_err(|e| format!("Invalid hotkeys: {}", e))?;
+		crate::hotkeys::parse_bindings(&bindings)?;
+	}
+	Ok(())
+}
+
+// Secrets to write to the vault: the sealed ones when a passphrase is given, plus any left in plain text in a
+// hand-edited file
+fn incoming_secrets(
+	file: &ExportFile,
+	passphrase: Option<&str>,
+) -> Result<(BTreeMap<String, String>, bool), String> {
+	let mut secrets = match (&file.secrets, passphrase) {
+		(Some(sealed), Some(passphrase)) => vault::open_with_passphrase(passphrase, sealed)?,
+		_ => BTreeMap::new(),
+	};
+	let skipped = file.secrets.is_some() && passphrase.is_none();
+	for (key, value) in &file.settings {
+		if let Some(value) = value.as_str().filter(|_| vault::SECRET_KEYS.contains(&key.as_str())) {
+			secrets.insert(key.clone(), value.trim().to_string());
+		}
+	}
+	Ok((secrets, skipped))
+}
+
+fn current_settings(app: &AppHandle) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+	let store = app
+		.store(settings::STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	Ok(store.entries().into_iter().collect())
+}
+
+fn diff(
+	current: &serde_json::Map<String, serde_json::Value>,
+	file: &ExportFile,
+	mode: ImportMode,
+) -> Vec<SettingChange> {
+	let mut changes: Vec<SettingChange> = file
+		.settings
+		.iter()
+		.filter(|(key, _)| is_portable(key))
+		.filter_map(|(key, incoming)| {
+			let current = current.get(key).cloned();
+			let kind = match &current {
+				None => ChangeKind::Added,
+				Some(current) if current != incoming => ChangeKind::Changed,
+				Some(_) => return None,
+			};
+			Some(SettingChange {
+				key: key.clone(),
+				kind,
+				current,
+				incoming: Some(incoming.clone()),
+			})
+		})
+		.collect();
+
+	if mode == ImportMode::Replace {
+		changes.extend(
+			current
+				.iter()
+				.filter(|(key, _)| is_portable(key) && !file.settings.contains_key(*key))
+				.map(|(key, current)| SettingChange {
+					key: key.clone(),
+					kind: ChangeKind::Removed,
+					current: Some(current.clone()),
+					incoming: None,
+				}),
+		);
+	}
+	changes.sort_by(|a, b| a.key.cmp(&b.key));
+	changes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn map(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+		value.as_object().cloned().unwrap()
+	}
+
+	fn export(settings: serde_json::Value) -> ExportFile {
+		ExportFile {
+			format: EXPORT_FORMAT.to_string(),
+			version: EXPORT_VERSION,
+			schema_version: settings::SCHEMA_VERSION,
+			exported_at: String::new(),
+			settings: map(settings),
+			secrets: None,
+		}
+	}
+
+	fn summary(changes: &[SettingChange]) -> Vec<(&str, &'static str)> {
+		changes
+			.iter()
+			.map(|change| {
+				let kind = match change.kind {
+					ChangeKind::Added => "added",
+					ChangeKind::Changed => "changed",
+					ChangeKind::Removed => "removed",
+				};
+				(change.key.as_str(), kind)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn merge_keeps_keys_the_file_lacks() {
+		let current = map(json!({ "ollamaModel": "a", "enableThinking": true, "systemPrompt": "x" }));
+		let file = export(json!({ "ollamaModel": "b", "enableThinking": true, "ollamaUrl": "http://h:1" }));
+		let changes = diff(&current, &file, ImportMode::Merge);
+		assert_eq!(summary(&changes), [("ollamaModel", "changed"), ("ollamaUrl", "added")]);
+		assert_eq!(changes[0].current, Some(json!("a")));
+		assert_eq!(changes[0].incoming, Some(json!("b")));
+	}
+
+	#[test]
+	fn replace_removes_keys_the_file_lacks() {
+		let current = map(json!({ "ollamaModel": "a", "systemPrompt": "x" }));
+		let file = export(json!({ "ollamaModel": "a" }));
+		let changes = diff(&current, &file, ImportMode::Replace);
+		assert_eq!(summary(&changes), [("systemPrompt", "removed")]);
+		assert_eq!(changes[0].incoming, None);
+	}
+
+	#[test]
+	fn replace_keeps_machine_specific_and_secret_keys() {
+		let current = map(json!({
+			"localDocsFolders": ["/home/me/docs"],
+			"localApi": { "enabled": true },
+			"webSearchApiKey": "key",
+			"schemaVersion": 2
+		}));
+		let file = export(json!({ "localDocsFolders": ["/elsewhere"] }));
+		assert!(diff(&current, &file, ImportMode::Replace).is_empty());
+	}
+
+	#[test]
+	fn strips_excluded_keys_inside_profiles() {
+		let mut values = map(json!({
+			"profiles": { "work": { "ollamaModel": "b", "localApi": { "enabled": true } } }
+		}));
+		sanitize_profiles(&mut values).unwrap();
+		assert_eq!(values["profiles"], json!({ "work": { "ollamaModel": "b" } }));
+	}
+
+	#[test]
+	fn active_profile_must_exist_after_import() {
+		let current = map(json!({ "profiles": { "work": {} } }));
+		let file = export(json!({ "activeProfile": "work" }));
+		assert!(check_active_profile(&current, &file, ImportMode::Merge).is_ok());
+		assert!(check_active_profile(&current, &file, ImportMode::Replace).is_err());
+
+		let file = export(json!({ "activeProfile": "home", "profiles": { "work": {} } }));
+		assert!(check_active_profile(&current, &file, ImportMode::Merge).is_err());
+	}
+}
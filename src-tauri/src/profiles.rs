@@ -0,0 +1,113 @@
+use std::collections::BTreeSet;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::{health, settings, vault};
+
+// Keys a profile can't override: the profile bookkeeping itself and the file's schema version
+const RESERVED_KEYS: &[&str] = &[
+	settings::PROFILES_KEY,
+	settings::ACTIVE_PROFILE_KEY,
+	settings::SCHEMA_VERSION_KEY,
+];
+
+// Profile names in a stable order, as shown in the tray and cycled by the hotkey
+pub fn names(app: &AppHandle) -> Vec<String> {
+	let mut names: Vec<String> = settings::read::<serde_json::Map<String, serde_json::Value>>(
+		app,
+		settings::PROFILES_KEY,
+	)
+	.map(|profiles| profiles.keys().cloned().collect())
+	.unwrap_or_default();
+	names.sort();
+	names
+}
+
+pub fn active(app: &AppHandle) -> Option<String> {
+	settings::active_profile(app).map(|(name, _)| name)
+}
+
+pub fn is_reserved_key(key: &str) -> bool {
+	RESERVED_KEYS.contains(&key)
+}
+
+// Check a profile's overrides: no bookkeeping keys, no secrets, and values the top-level settings would accept
+pub fn validate(values: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+	if let Some(key) = values.keys().find(|key| RESERVED_KEYS.contains(&key.as_str())) {
+		return Err(format!("Profiles can't set {}", key));
+	}
+	if let Some(key) = values.keys().find(|key| vault::is_secret_key(key)) {
+		return Err(format!("Profiles can't hold secrets ({})", key));
+	}
+	settings::validate_values(values)
+}
+
+// Apply a profile, or go back to the top-level settings with `None`. Every key either profile overrides is
+// announced as changed, so hotkeys, the tray and cached configuration pick it up at once.
+pub fn switch(app: &AppHandle, name: Option<&str>) -> Result<(), String> {
+	let previous = settings::active_profile(app);
+	if previous.as_ref().map(|(name, _)| name.as_str()) == name {
+		return Ok(());
+	}
+
+	let next = match name {
+		Some(name) => {
+			let values = settings::read::<serde_json::Map<String, serde_json::Value>>(
+				app,
+				settings::PROFILES_KEY,
+			)
+			.and_then(|profiles| profiles.get(name)?.as_object().cloned())
+			.ok_or_else(|| format!("Unknown profile \"{}\"", name))?;
+			validate(&values).map_err(|e| format!("Profile \"{}\": {}", name, e))?;
+			Some(values)
+		}
+		None => None,
+	};
+
+	let store = app
+		.store(settings::STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	match name {
+		Some(name) => store.set(settings::ACTIVE_PROFILE_KEY, name),
+		None => {
+			store.delete(settings::ACTIVE_PROFILE_KEY);
+		}
+	}
+	store
+		.save()
+		.map_err(|e| format!("Failed to save settings: {}", e))?;
+
+	let mut keys: BTreeSet<String> = previous
+		.map(|(_, values)| values)
+		.into_iter()
+		.chain(next)
+		.flat_map(|values| values.into_iter().map(|(key, _)| key))
+		.collect();
+	keys.insert(settings::ACTIVE_PROFILE_KEY.to_string());
+	let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+	settings::notify_updated(app, &keys);
+	log::info!("[profiles] Switched to {}", name.unwrap_or("default settings"));
+
+	// The Ollama server may have changed; don't wait for the next scheduled check
+	let app = app.clone();
+	tauri::async_runtime::spawn(async move {
+		health::check(&app).await;
+	});
+	Ok(())
+}
+
+// Switch to the next profile after the active one, with the top-level settings between the last and the first.
+// Returns the new profile's name.
+pub fn cycle(app: &AppHandle) -> Result<Option<String>, String> {
+	let names = names(app);
+	let next = match active(app) {
+		None => names.first().cloned(),
+		Some(current) => names
+			.iter()
+			.position(|name| *name == current)
+			.and_then(|index| names.get(index + 1))
+			.cloned(),
+	};
+	switch(app, next.as_deref())?;
+	Ok(next)
+}
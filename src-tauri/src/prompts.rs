@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use crate::settings::{self, SettingsSource};
+
+// System prompt for quick AI responses
+pub const QUICK_ANSWER_SYSTEM_PROMPT: &str = r#"You are a web search agent. Your only job is to answer the user's query using fresh information from the internet.
+
+Rules:
+- Always call the tool `web_search` exactly once per user query.
+- Use the tool results as your primary source of truth.
+- Return a single, direct answer to the user based only on the tool results and common knowledge needed for readability.
+- Do not ask follow-up questions. Do not start or continue a conversation. Do not add suggestions or next steps.
+- If the results are conflicting, summarize the consensus and note uncertainty briefly.
+- If the results are insufficient, say so in one sentence and state what could not be verified.
+
+Output:
+- Respond with only the final answer text (no tool logs, no reasoning, no citations unless the application requires them)."#;
+
+// System prompt for models that cannot call tools, so they answer from their own knowledge
+pub const QUICK_ANSWER_NO_TOOLS_SYSTEM_PROMPT: &str = r#"You are a quick answer assistant. Answer the user's query directly from your own knowledge.
+
+Rules:
+- Return a single, direct answer. Do not ask follow-up questions or add suggestions or next steps.
+- If the query needs information you may not have, such as recent events, say so in one sentence.
+
+Output:
+- Respond with only the final answer text (no reasoning)."#;
+
+// Appended to the system prompt when the local knowledge base has indexed documents
+pub const LOCAL_DOCS_PROMPT_ADDENDUM: &str = r#"
+
+Local documents:
+- The tool `search_local_docs` searches the user's own notes and documents.
+- Call it as well when the query is about the user's projects, notes or internal documentation, and prefer its results for such questions."#;
+
+// Which built-in prompt a quick answer starts from, keys of the `systemPromptOverrides` setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptMode {
+	WebSearch,
+	NoTools,
+}
+
+impl PromptMode {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			PromptMode::WebSearch => "webSearch",
+			PromptMode::NoTools => "noTools",
+		}
+	}
+
+	fn default_prompt(self) -> &'static str {
+		match self {
+			PromptMode::WebSearch => QUICK_ANSWER_SYSTEM_PROMPT,
+			PromptMode::NoTools => QUICK_ANSWER_NO_TOOLS_SYSTEM_PROMPT,
+		}
+	}
+}
+
+// The system prompt for a mode, unrendered: the per-mode override, then `systemPrompt`, then the built-in one
+pub fn system_prompt(source: &impl SettingsSource, mode: PromptMode) -> String {
+	let overrides: HashMap<String, String> =
+		settings::read(source, "systemPromptOverrides").unwrap_or_default();
+	overrides
+		.get(mode.as_str())
+		.cloned()
+		.or_else(|| settings::read::<String>(source, "systemPrompt"))
+		.filter(|prompt| !prompt.trim().is_empty())
+		.unwrap_or_else(|| mode.default_prompt().to_string())
+}
+
+// Values substituted for `{date}`, `{locale}` and `{os}` in system prompts
+pub struct PromptVars {
+	pub date: String,
+	pub locale: String,
+	pub os: String,
+}
+
+impl PromptVars {
+	pub fn current(source: &impl SettingsSource) -> Self {
+		PromptVars {
+			date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+			locale: locale(source),
+			os: os_name().to_string(),
+		}
+	}
+}
+
+// Replace the known variables; unknown `{...}` text is left as typed
+pub fn render(prompt: &str, vars: &PromptVars) -> String {
+	prompt
+		.replace("{date}", &vars.date)
+		.replace("{locale}", &vars.locale)
+		.replace("{os}", &vars.os)
+}
+
+// Date, time, time zone and locale, appended to every quick answer prompt unless `includePromptContext` is off.
+// Without it "latest" and "this year" queries are answered relative to the model's training cutoff.
+pub fn context_block(source: &impl SettingsSource) -> Option<String> {
+	if !settings::read::<bool>(source, "includePromptContext").unwrap_or(true) {
+		return None;
+	}
+
+	let now = chrono::Local::now();
+	let time_zone = iana_time_zone::get_timezone().unwrap_or_else(|_| "unknown".to_string());
+	Some(format!(
+		"\n\nContext:\n- Current date: {}\n- Local time: {} (UTC{})\n- Time zone: {}\n- User locale: {}",
+		now.format("%A, %Y-%m-%d"),
+		now.format("%H:%M"),
+		now.format("%:z"),
+		time_zone,
+		locale(source)
+	))
+}
+
+// The `locale` setting, else the POSIX locale environment, as a BCP 47 tag such as "en-US"
+pub fn locale(source: &impl SettingsSource) -> String {
+	settings::read::<String>(source, "locale")
+		.into_iter()
+		.chain(
+			["LC_ALL", "LC_MESSAGES", "LANG"]
+				.iter()
+				.filter_map(|key| std::env::var(key).ok()),
+		)
+		.map(|value| {
+			// "de_DE.UTF-8" or "de_DE@euro" -> "de-DE"
+			value
+				.split(['.', '@'])
+				.next()
+				.unwrap_or_default()
+				.trim()
+				.replace('_', "-")
+		})
+		.find(|tag| !tag.is_empty() && tag != "C" && tag != "POSIX")
+		.unwrap_or_else(|| "en-US".to_string())
+}
+
+fn os_name() -> &'static str {
+	match std::env::consts::OS {
+		"macos" => "macOS",
+		"windows" => "Windows",
+		"linux" => "Linux",
+		other => other,
+	}
+}
@@ -0,0 +1,515 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::vault::{self, Vault};
+use crate::{api, clipboard, logging, ollama, templates};
+
+// Owned by the backend; the frontend reads and writes it through the `get_settings` and `update_settings` commands
+pub const STORE_PATH: &str = "settings.json";
+
+// Bumped whenever stored keys or value shapes change; `migrate` brings older files up to date
+pub const SCHEMA_VERSION: u64 = 2;
+pub const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+// Named sets of setting overrides, and the one currently applied
+pub const PROFILES_KEY: &str = "profiles";
+pub const ACTIVE_PROFILE_KEY: &str = "activeProfile";
+
+// Keys read by feature modules rather than `Settings`. `update` saves them after checking them against the type
+// their module reads; `hotkeys` is left to `update_hotkeys`, which registers the bindings before saving them.
+pub const FEATURE_KEYS: &[&str] = &[
+	"clipboardActionMode",
+	"embeddingModel",
+	"generationOptions",
+	"includePromptContext",
+	"keepAlive",
+	"localApi",
+	"localDocsFolders",
+	"locale",
+	"logPayloadLimit",
+	"logPrivacy",
+	"modelProfiles",
+	"promptTemplates",
+	"systemPrompt",
+	"systemPromptOverrides",
+	"warmUpOnOpen",
+];
+
+// Where settings are read from: the app's store while it runs, or the settings file itself for the CLI
+pub trait SettingsSource {
+	// A top-level value from `settings.json`, ignoring profiles
+	fn stored(&self, key: &str) -> Option<serde_json::Value>;
+	// A decrypted secret from the vault
+	fn secret(&self, key: &str) -> Option<String>;
+}
+
+impl SettingsSource for AppHandle {
+	fn stored(&self, key: &str) -> Option<serde_json::Value> {
+		self.store(STORE_PATH).ok()?.get(key)
+	}
+
+	fn secret(&self, key: &str) -> Option<String> {
+		self.try_state::<Vault>()?.get(key)
+	}
+}
+
+// A read-only snapshot of the settings file and vault in the app's data directory, for use without the app
+pub struct SettingsFile {
+	values: serde_json::Map<String, serde_json::Value>,
+	vault: Option<Vault>,
+}
+
+impl SettingsFile {
+	pub fn load(data_dir: &std::path::Path) -> Self {
+		let values = std::fs::read(data_dir.join(STORE_PATH))
+			.ok()
+			.and_then(|bytes| serde_json::from_slice(&bytes).ok())
+			.unwrap_or_default();
+		let vault = Vault::open(data_dir)
+			.map_err(|e| log::warn!("[vault] {}", e))
+			.ok();
+		SettingsFile { values, vault }
+	}
+}
+
+impl SettingsSource for SettingsFile {
+	fn stored(&self, key: &str) -> Option<serde_json::Value> {
+		self.values.get(key).cloned()
+	}
+
+	fn secret(&self, key: &str) -> Option<String> {
+		self.vault.as_ref()?.get(key)
+	}
+}
+
+// Read a single setting, treating missing or malformed values as unset. The active profile's value wins over the
+// top-level one.
+pub fn read<T: DeserializeOwned>(source: &impl SettingsSource, key: &str) -> Option<T> {
+	let value = active_profile(source)
+		.and_then(|(_, values)| values.get(key).cloned())
+		.or_else(|| source.stored(key))?;
+	serde_json::from_value(value).ok()
+}
+
+// The selected profile's name and overrides, if a profile is selected and still exists
+pub fn active_profile(
+	source: &impl SettingsSource,
+) -> Option<(String, serde_json::Map<String, serde_json::Value>)> {
+	let name = source.stored(ACTIVE_PROFILE_KEY)?.as_str()?.to_string();
+	let values = source.stored(PROFILES_KEY)?.get(&name)?.as_object()?.clone();
+	Some((name, values))
+}
+
+// Write a single setting where `read` finds it, in the active profile when that overrides the key like `update`
+// does, and flush the store to disk
+pub fn write<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), String> {
+	let store = app
+		.store(STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	let value =
+		serde_json::to_value(value).map_err(|e| format!("Failed to serialize {}: {}", key, e))?;
+	match active_profile(app).filter(|(_, values)| values.contains_key(key)) {
+		Some((name, mut values)) => {
+			values.insert(key.to_string(), value);
+			let mut profiles = store
+				.get(PROFILES_KEY)
+				.and_then(|profiles| profiles.as_object().cloned())
+				.unwrap_or_default();
+			profiles.insert(name, serde_json::Value::Object(values));
+			store.set(PROFILES_KEY, serde_json::Value::Object(profiles));
+		}
+		None => store.set(key, value),
+	}
+	store
+		.save()
+		.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+// Secrets that are only sent to the endpoint another key sets; a profile that moves the endpoint needs its own
+const SECRET_ENDPOINTS: &[(&str, &str)] = &[("webSearchApiKey", "webSearchApiUrl")];
+
+// The vault name of the active profile's own copy of a secret, when it has one or overrides the secret's endpoint
+fn profile_secret_name(source: &impl SettingsSource, key: &str) -> Option<String> {
+	let (name, values) = active_profile(source)?;
+	let scoped = vault::profile_secret_name(key, &name);
+	let moves_endpoint = SECRET_ENDPOINTS
+		.iter()
+		.any(|(secret, endpoint)| *secret == key && values.contains_key(*endpoint));
+	(moves_endpoint || source.secret(&scoped).is_some()).then_some(scoped)
+}
+
+// Decrypt a secret setting from the vault; unset when the vault is unavailable. The active profile's own secret
+// wins, and a profile that overrides the secret's endpoint never falls back to the top-level one.
+pub fn read_secret(source: &impl SettingsSource, key: &str) -> Option<String> {
+	match profile_secret_name(source, key) {
+		Some(scoped) => source.secret(&scoped),
+		None => source.secret(key),
+	}
+}
+
+fn write_secret(app: &AppHandle, key: &str, value: &str) -> Result<(), String> {
+	app.try_state::<Vault>()
+		.ok_or_else(|| "Secure storage is unavailable".to_string())?
+		.set(key, value)
+}
+
+// Tell the windows and backend listeners which keys changed, like the Options window does after saving
+pub fn notify_updated(app: &AppHandle, keys: &[&str]) {
+	let _ = app.emit("settings://updated", serde_json::json!({ "keys": keys }));
+}
+
+// The settings edited in the Options window. Commands load these themselves instead of taking them as arguments;
+// feature-specific keys (hotkeys, templates, ...) are still read by their own modules.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+	// Empty means Ollama's default local address
+	pub ollama_url: String,
+	pub ollama_model: String,
+	pub enable_thinking: bool,
+	pub web_search_api_url: String,
+	pub web_search_api_key: String,
+	pub translation_second_language: String,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Settings {
+			ollama_url: String::new(),
+			ollama_model: String::new(),
+			enable_thinking: true,
+			web_search_api_url: String::new(),
+			web_search_api_key: String::new(),
+			translation_second_language: String::new(),
+		}
+	}
+}
+
+// The invalid value last reported for each key, so a bad hand edit is logged once rather than on every load
+static REPORTED_INVALID: Mutex<Vec<(&'static str, String)>> = Mutex::new(Vec::new());
+
+fn report_invalid(key: &'static str, value: &str) {
+	let mut reported = REPORTED_INVALID.lock().expect("settings mutex poisoned");
+	if reported.iter().any(|(k, v)| *k == key && v == value) {
+		return;
+	}
+	reported.retain(|(k, _)| *k != key);
+	reported.push((key, value.to_string()));
+	log::warn!("[settings] Ignoring invalid {} {}, using the default", key, logging::text(value));
+}
+
+impl Settings {
+	// Load from the store, replacing invalid values with defaults so a hand-edited file can't break requests
+	pub fn load(source: &impl SettingsSource) -> Self {
+		let defaults = Settings::default();
+		let mut settings = Settings {
+			ollama_url: read(source, "ollamaUrl").unwrap_or(defaults.ollama_url.clone()),
+			ollama_model: read(source, "ollamaModel").unwrap_or(defaults.ollama_model.clone()),
+			enable_thinking: read(source, "enableThinking").unwrap_or(defaults.enable_thinking),
+			web_search_api_url: read(source, "webSearchApiUrl")
+				.unwrap_or(defaults.web_search_api_url.clone()),
+			web_search_api_key: read_secret(source, "webSearchApiKey")
+				.unwrap_or(defaults.web_search_api_key.clone()),
+			translation_second_language: read(source, "translationSecondLanguage")
+				.unwrap_or(defaults.translation_second_language.clone()),
+		}
+		.normalized();
+
+		if validate_url("Ollama URL", &settings.ollama_url).is_err() {
+			report_invalid("ollamaUrl", &settings.ollama_url);
+			settings.ollama_url = defaults.ollama_url;
+		}
+		if validate_model(&settings.ollama_model).is_err() {
+			report_invalid("ollamaModel", &settings.ollama_model);
+			settings.ollama_model = defaults.ollama_model;
+		}
+		if validate_url("Web search API URL", &settings.web_search_api_url).is_err() {
+			report_invalid("webSearchApiUrl", &settings.web_search_api_url);
+			settings.web_search_api_url = defaults.web_search_api_url;
+		}
+		if validate_language(&settings.translation_second_language).is_err() {
+			report_invalid("translationSecondLanguage", &settings.translation_second_language);
+			settings.translation_second_language = defaults.translation_second_language;
+		}
+		settings
+	}
+
+	fn normalized(self) -> Self {
+		Settings {
+			ollama_url: self.ollama_url.trim().trim_end_matches('/').to_string(),
+			ollama_model: self.ollama_model.trim().to_string(),
+			web_search_api_url: self.web_search_api_url.trim().to_string(),
+			web_search_api_key: self.web_search_api_key.trim().to_string(),
+			translation_second_language: self.translation_second_language.trim().to_string(),
+			..self
+		}
+	}
+
+	pub fn validate(&self) -> Result<(), String> {
+		validate_url("Ollama URL", &self.ollama_url)?;
+		validate_model(&self.ollama_model)?;
+		validate_url("Web search API URL", &self.web_search_api_url)?;
+		validate_language(&self.translation_second_language)
+	}
+
+	// What the frontend gets: secrets blanked, plus `storedSecrets` naming the ones that are set, so the Options
+	// window can show that a key is saved without ever receiving it
+	pub fn public_view(&self) -> Result<serde_json::Value, String> {
+		let mut value =
+			serde_json::to_value(self).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+		let fields = value
+			.as_object_mut()
+			.ok_or_else(|| "Failed to serialize settings".to_string())?;
+		let mut stored = Vec::new();
+		for key in vault::SECRET_KEYS {
+			if let Some(secret) = fields.get_mut(*key) {
+				if secret.as_str().is_some_and(|s| !s.is_empty()) {
+					stored.push(*key);
+				}
+				*secret = serde_json::Value::String(String::new());
+			}
+		}
+		fields.insert("storedSecrets".to_string(), serde_json::json!(stored));
+		Ok(value)
+	}
+}
+
+// Check stored values (e.g. from an imported file or a profile) the way `update` checks a patch; keys it doesn't
+// know are ignored
+pub fn validate_values(values: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+	for (key, value) in values {
+		if FEATURE_KEYS.contains(&key.as_str()) {
+			validate_feature_value(key, value)?;
+		}
+	}
+	let mut merged = serde_json::to_value(Settings::default())
+		.map_err(|e| format!("Failed to serialize settings: {}", e))?;
+	let fields = merged
+		.as_object_mut()
+		.ok_or_else(|| "Failed to serialize settings".to_string())?;
+	for (key, value) in values {
+		if fields.contains_key(key) {
+			fields.insert(key.clone(), value.clone());
+		}
+	}
+	serde_json::from_value::<Settings>(merged)
+		.map_err(|e| format!("Invalid settings: {}", e))?
+		.normalized()
+		.validate()
+}
+
+// Check a feature key's value against the type its module reads, so a saved value can't be silently ignored
+fn validate_feature_value(key: &str, value: &serde_json::Value) -> Result<(), String> {
+	fn parse<T: DeserializeOwned>(key: &str, value: &serde_json::Value) -> Result<T, String> {
+		serde_json::from_value(value.clone()).map_err(|e| format!("Invalid {}: {}", key, e))
+	}
+
+	match key {
+		"clipboardActionMode" => parse::<clipboard::ClipboardMode>(key, value).map(|_| ()),
+		"embeddingModel" => validate_model(parse::<String>(key, value)?.trim()),
+		"generationOptions" => parse::<ollama::GenerationOptions>(key, value).map(|_| ()),
+		"includePromptContext" | "warmUpOnOpen" => parse::<bool>(key, value).map(|_| ()),
+		"keepAlive" => parse::<ollama::KeepAlive>(key, value).map(|_| ()),
+		"localApi" => api::validate_config(&parse(key, value)?),
+		"localDocsFolders" => parse::<Vec<String>>(key, value).map(|_| ()),
+		"locale" => validate_language(parse::<String>(key, value)?.trim()),
+		"logPayloadLimit" => parse::<usize>(key, value).map(|_| ()),
+		"logPrivacy" => parse::<logging::PrivacyLevel>(key, value).map(|_| ()),
+		"modelProfiles" => {
+			parse::<HashMap<String, ollama::ModelProfileOverride>>(key, value).map(|_| ())
+		}
+		"promptTemplates" => parse::<Vec<templates::PromptTemplate>>(key, value).map(|_| ()),
+		"systemPrompt" => parse::<String>(key, value).map(|_| ()),
+		"systemPromptOverrides" => parse::<HashMap<String, String>>(key, value).map(|_| ()),
+		_ => Err(format!("Unknown setting: {}", key)),
+	}
+}
+
+fn validate_model(model: &str) -> Result<(), String> {
+	if model.chars().any(char::is_whitespace) {
+		return Err(format!("Invalid model name \"{}\"", model));
+	}
+	Ok(())
+}
+
+fn validate_url(label: &str, url: &str) -> Result<(), String> {
+	if url.is_empty() {
+		return Ok(());
+	}
+	match reqwest::Url::parse(url) {
+		Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+		Ok(_) => Err(format!("{} must use http or https: {}", label, url)),
+		Err(e) => Err(format!("Invalid {} \"{}\": {}", label, url, e)),
+	}
+}
+
+// Empty, or a language code such as "de", "pt-BR" or "zh-CN"
+pub fn validate_language(code: &str) -> Result<(), String> {
+	if code.is_empty() {
+		return Ok(());
+	}
+	let mut parts = code.split('-');
+	let language_ok = parts.next().is_some_and(|language| {
+		(2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic())
+	});
+	let region_ok = parts.all(|part| {
+		(2..=4).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+	});
+	if language_ok && region_ok {
+		Ok(())
+	} else {
+		Err(format!("Invalid language code \"{}\"", code))
+	}
+}
+
+// Apply a partial update in camelCase from the frontend: validate the result, save the keys that changed (secrets
+// to the vault, keys the active profile overrides or keeps its own secret for to that profile) and announce them
+// with `settings://updated`.
+// Besides the `Settings` fields, the patch may hold any of the FEATURE_KEYS.
+pub fn update(app: &AppHandle, values: &serde_json::Value) -> Result<Settings, String> {
+	let patch = values
+		.as_object()
+		.ok_or_else(|| "Settings update must be an object".to_string())?;
+
+	let mut merged = serde_json::to_value(Settings::load(app))
+		.map_err(|e| format!("Failed to serialize settings: {}", e))?;
+	let fields = merged
+		.as_object_mut()
+		.ok_or_else(|| "Failed to serialize settings".to_string())?;
+	for (key, value) in patch {
+		if fields.contains_key(key) {
+			fields.insert(key.clone(), value.clone());
+		} else if key == "hotkeys" {
+			return Err("Hotkeys are saved with update_hotkeys".to_string());
+		} else {
+			validate_feature_value(key, value)?;
+		}
+	}
+	let updated = serde_json::from_value::<Settings>(merged)
+		.map_err(|e| format!("Invalid settings: {}", e))?
+		.normalized();
+	updated.validate()?;
+
+	let store = app
+		.store(STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	let updated_value =
+		serde_json::to_value(&updated).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+	let mut changed: Vec<&str> = Vec::new();
+	let mut store_changed = false;
+	let mut profile = active_profile(app);
+	let mut profile_changed = false;
+	for (key, patched) in patch {
+		let value = match updated_value.get(key.as_str()) {
+			Some(normalized) => normalized.clone(),
+			None => patched.clone(),
+		};
+		if vault::SECRET_KEYS.contains(&key.as_str()) {
+			let secret = value.as_str().unwrap_or_default();
+			if read_secret(app, key).unwrap_or_default() != secret {
+				let name = profile_secret_name(app, key).unwrap_or_else(|| key.clone());
+				write_secret(app, &name, secret)?;
+				changed.push(key);
+			}
+		} else if let Some((_, values)) = profile
+			.as_mut()
+			.filter(|(_, values)| values.contains_key(key))
+		{
+			if values.get(key) != Some(&value) {
+				values.insert(key.clone(), value);
+				profile_changed = true;
+				changed.push(key);
+			}
+		} else if store.get(key).as_ref() != Some(&value) {
+			store.set(key.as_str(), value);
+			store_changed = true;
+			changed.push(key);
+		}
+	}
+	if changed.is_empty() {
+		return Ok(updated);
+	}
+	if let Some((name, values)) = profile.filter(|_| profile_changed) {
+		let mut profiles = store
+			.get(PROFILES_KEY)
+			.and_then(|profiles| profiles.as_object().cloned())
+			.unwrap_or_default();
+		profiles.insert(name, serde_json::Value::Object(values));
+		store.set(PROFILES_KEY, serde_json::Value::Object(profiles));
+		store_changed = true;
+	}
+	if store_changed {
+		store
+			.save()
+			.map_err(|e| format!("Failed to save settings: {}", e))?;
+	}
+	notify_updated(app, &changed);
+	Ok(updated)
+}
+
+type Migration = fn(&AppHandle) -> Result<(), String>;
+
+// Migrations in order; the one at index i upgrades a file from version i to i + 1
+const MIGRATIONS: &[Migration] = &[migrate_v1, migrate_v2];
+
+// v1: strings used to be saved untrimmed, and an empty `ollamaModel` was saved when Ollama was unreachable on first
+// run, which kept the first-model detection from running again
+fn migrate_v1(app: &AppHandle) -> Result<(), String> {
+	let store = app
+		.store(STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	for key in ["ollamaModel", "webSearchApiUrl", "webSearchApiKey", "translationSecondLanguage"] {
+		let Some(serde_json::Value::String(value)) = store.get(key) else {
+			continue;
+		};
+		let trimmed = value.trim();
+		if key == "ollamaModel" && trimmed.is_empty() {
+			store.delete(key);
+		} else if trimmed != value {
+			store.set(key, trimmed);
+		}
+	}
+	Ok(())
+}
+
+// v2: secrets used to be stored in plain text in `settings.json`; move them into the vault
+fn migrate_v2(app: &AppHandle) -> Result<(), String> {
+	let store = app
+		.store(STORE_PATH)
+		.map_err(|e| format!("Failed to open settings store: {}", e))?;
+	for key in vault::SECRET_KEYS {
+		let Some(value) = store.get(*key) else {
+			continue;
+		};
+		if let Some(secret) = value.as_str().filter(|s| !s.is_empty()) {
+			write_secret(app, key, secret)?;
+		}
+		store.delete(*key);
+	}
+	Ok(())
+}
+
+// Bring the settings file up to SCHEMA_VERSION; called once on startup before anything reads settings
+pub fn migrate(app: &AppHandle) -> Result<(), String> {
+	let version = read::<u64>(app, SCHEMA_VERSION_KEY).unwrap_or(0);
+	if version >= SCHEMA_VERSION {
+		if version > SCHEMA_VERSION {
+			log::warn!(
+				"[settings] Schema version {} is newer than this build ({}), leaving it as is",
+				version,
+				SCHEMA_VERSION
+			);
+		}
+		return Ok(());
+	}
+
+	for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+		migration(app).map_err(|e| format!("Settings migration to v{} failed: {}", from + 1, e))?;
+		write(app, SCHEMA_VERSION_KEY, &(from as u64 + 1))?;
+		log::info!("[settings] Migrated to schema v{}", from + 1);
+	}
+	Ok(())
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ollama;
+use crate::settings::{self, SettingsSource};
+
+// A user-defined quick action, picked when the query starts with its prefix, e.g. "/fix"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+	pub prefix: String,
+	pub system_prompt: String,
+	// Falls back to the model selected in Options
+	#[serde(default)]
+	pub model: Option<String>,
+	// Falls back to the thinking toggle in Options
+	#[serde(default)]
+	pub thinking: Option<bool>,
+	// Tool names the template may use; unset allows every available tool, empty allows none
+	#[serde(default)]
+	pub tools: Option<Vec<String>>,
+	// Merged over the `generationOptions` setting, field by field
+	#[serde(default)]
+	pub options: Option<ollama::GenerationOptions>,
+}
+
+impl PromptTemplate {
+	pub fn allows_tool(&self, name: &str) -> bool {
+		self.tools
+			.as_ref()
+			.is_none_or(|tools| tools.iter().any(|tool| tool == name))
+	}
+}
+
+// Templates from the `promptTemplates` setting
+pub fn configured(source: &impl SettingsSource) -> Vec<PromptTemplate> {
+	settings::read(source, "promptTemplates").unwrap_or_default()
+}
+
+// Find the template whose prefix starts the query and return it with the rest of the query.
+// The prefix must be followed by whitespace or the end of the text, so "/fixture" doesn't match "/fix".
+pub fn match_prefix(templates: &[PromptTemplate], text: &str) -> Option<(PromptTemplate, String)> {
+	let text = text.trim_start();
+	templates
+		.iter()
+		.filter(|template| !template.prefix.trim().is_empty())
+		.find_map(|template| {
+			let rest = text.strip_prefix(template.prefix.trim())?;
+			if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+				return None;
+			}
+			Some((template.clone(), rest.trim().to_string()))
+		})
+}
@@ -0,0 +1,289 @@
+use tauri::{
+	menu::{CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+	tray::TrayIconBuilder,
+	AppHandle, Manager, WebviewWindowBuilder,
+};
+
+use crate::{clipboard, health, history, ollama, profiles, settings};
+
+const TRAY_ID: &str = "main";
+const RECENT_QUERIES: usize = 5;
+const RECENT_LABEL_CHARS: usize = 40;
+
+// Menu item ids that carry a value after the prefix
+const RECENT_PREFIX: &str = "recent:";
+const MODEL_PREFIX: &str = "model:";
+// Followed by the profile name, or nothing for the top-level settings
+const PROFILE_PREFIX: &str = "profile:";
+
+// Build the menu from current state; called on startup and whenever that state changes.
+// `models` comes from Ollama, which is fetched before building since menus are built synchronously.
+fn build_menu(app: &AppHandle, models: &[String]) -> tauri::Result<Menu<tauri::Wry>> {
+	let health = app.state::<health::HealthState>().current();
+	let status_item =
+		MenuItem::with_id(app, "ollama_status", health.summary(), false, None::<&str>)?;
+
+	let recent_items = app
+		.state::<history::History>()
+		.recent(RECENT_QUERIES)
+		.into_iter()
+		.map(|entry| {
+			MenuItem::with_id(
+				app,
+				format!("{}{}", RECENT_PREFIX, entry.id),
+				recent_label(&entry.query),
+				true,
+				None::<&str>,
+			)
+		})
+		.collect::<tauri::Result<Vec<_>>>()?;
+	let recent_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = recent_items
+		.iter()
+		.map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+		.collect();
+	let recent_menu = Submenu::with_id_and_items(
+		app,
+		"recent",
+		"Recent Queries",
+		!recent_refs.is_empty(),
+		&recent_refs,
+	)?;
+
+	let current_settings = settings::Settings::load(app);
+	let model_items = models
+		.iter()
+		.map(|model| {
+			CheckMenuItem::with_id(
+				app,
+				format!("{}{}", MODEL_PREFIX, model),
+				model,
+				true,
+				*model == current_settings.ollama_model,
+				None::<&str>,
+			)
+		})
+		.collect::<tauri::Result<Vec<_>>>()?;
+	let model_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = model_items
+		.iter()
+		.map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+		.collect();
+	let model_menu =
+		Submenu::with_id_and_items(app, "models", "Model", !model_refs.is_empty(), &model_refs)?;
+
+	let profile_names = profiles::names(app);
+	let active_profile = profiles::active(app);
+	let profile_items = std::iter::once((String::new(), "Default".to_string()))
+		.chain(profile_names.iter().map(|name| (name.clone(), name.clone())))
+		.map(|(id, label)| {
+			let checked = active_profile.as_deref().unwrap_or_default() == id;
+			CheckMenuItem::with_id(
+				app,
+				format!("{}{}", PROFILE_PREFIX, id),
+				label,
+				true,
+				checked,
+				None::<&str>,
+			)
+		})
+		.collect::<tauri::Result<Vec<_>>>()?;
+	let profile_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = profile_items
+		.iter()
+		.map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+		.collect();
+	let profile_menu = Submenu::with_id_and_items(
+		app,
+		"profiles",
+		"Profile",
+		!profile_names.is_empty(),
+		&profile_refs,
+	)?;
+
+	let thinking_item = CheckMenuItem::with_id(
+		app,
+		"thinking",
+		"Thinking Mode",
+		true,
+		current_settings.enable_thinking,
+		None::<&str>,
+	)?;
+	#[cfg(desktop)]
+	let pause_item = CheckMenuItem::with_id(
+		app,
+		"pause_hotkeys",
+		"Pause Hotkeys",
+		true,
+		crate::hotkeys::is_paused(app),
+		None::<&str>,
+	)?;
+
+	let clipboard_item = MenuItem::with_id(
+		app,
+		"ask_clipboard",
+		"Ask about Clipboard",
+		true,
+		None::<&str>,
+	)?;
+	let options_item = MenuItem::with_id(app, "options", "Options", true, None::<&str>)?;
+	let quit_item = MenuItem::with_id(app, "quit", "Exit", true, None::<&str>)?;
+
+	let menu = Menu::with_items(
+		app,
+		&[
+			&status_item,
+			&PredefinedMenuItem::separator(app)?,
+			&recent_menu,
+			&clipboard_item,
+			&PredefinedMenuItem::separator(app)?,
+			&profile_menu,
+			&model_menu,
+			&thinking_item,
+		],
+	)?;
+	#[cfg(desktop)]
+	menu.append(&pause_item)?;
+	menu.append_items(&[
+		&PredefinedMenuItem::separator(app)?,
+		&options_item,
+		&quit_item,
+	])?;
+	Ok(menu)
+}
+
+// First line of the query, shortened to fit a menu
+fn recent_label(query: &str) -> String {
+	let line = query.lines().next().unwrap_or_default().trim();
+	if line.chars().count() > RECENT_LABEL_CHARS {
+		let shortened: String = line.chars().take(RECENT_LABEL_CHARS - 1).collect();
+		format!("{}…", shortened.trim_end())
+	} else {
+		line.to_string()
+	}
+}
+
+fn tooltip(app: &AppHandle) -> String {
+	format!(
+		"AI Spotlight - {}",
+		app.state::<health::HealthState>().current().summary()
+	)
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+	let menu = build_menu(app, &[])?;
+	TrayIconBuilder::with_id(TRAY_ID)
+		.icon(app.default_window_icon().unwrap().clone())
+		.tooltip(tooltip(app))
+		.menu(&menu)
+		.show_menu_on_left_click(true)
+		.on_menu_event(handle_menu_event)
+		.build(app)?;
+	refresh(app);
+	Ok(())
+}
+
+// Rebuild the menu and tooltip in the background, e.g. after settings, history or the Ollama status changed
+pub fn refresh(app: &AppHandle) {
+	let app = app.clone();
+	tauri::async_runtime::spawn(async move {
+		let Some(tray) = app.tray_by_id(TRAY_ID) else {
+			return;
+		};
+		// Without Ollama the model submenu is just left empty
+		let models: Vec<String> = ollama::list_models(false)
+			.await
+			.map(|models| models.into_iter().map(|model| model.name).collect())
+			.unwrap_or_default();
+		match build_menu(&app, &models) {
+			Ok(menu) => {
+				if let Err(e) = tray.set_menu(Some(menu)) {
+					log::warn!("[tray] Failed to set menu: {}", e);
+				}
+			}
+			Err(e) => log::warn!("[tray] Failed to build menu: {}", e),
+		}
+		let _ = tray.set_tooltip(Some(tooltip(&app)));
+	});
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+	let id = event.id.as_ref();
+	if let Some(entry_id) = id.strip_prefix(RECENT_PREFIX) {
+		reopen_recent(app, entry_id);
+		return;
+	}
+	if let Some(name) = id.strip_prefix(PROFILE_PREFIX) {
+		let name = Some(name).filter(|name| !name.is_empty());
+		if let Err(e) = profiles::switch(app, name) {
+			log::error!("[tray] {}", e);
+		}
+		// Re-check the clicked item even when nothing changed
+		refresh(app);
+		return;
+	}
+	if let Some(model) = id.strip_prefix(MODEL_PREFIX) {
+		update_setting(app, serde_json::json!({ "ollamaModel": model }));
+		return;
+	}
+
+	match id {
+		"ask_clipboard" => clipboard::trigger(app),
+		"thinking" => {
+			let enabled = settings::Settings::load(app).enable_thinking;
+			update_setting(app, serde_json::json!({ "enableThinking": !enabled }));
+		}
+		#[cfg(desktop)]
+		"pause_hotkeys" => {
+			if let Err(e) = crate::hotkeys::set_paused(app, !crate::hotkeys::is_paused(app)) {
+				log::error!("[hotkeys] {}", e);
+			}
+			refresh(app);
+		}
+		"options" => open_options(app),
+		"quit" => {
+			app.exit(0);
+		}
+		_ => {}
+	}
+}
+
+// Show a past answer in the panel and make it the one Shift+Enter pastes
+fn reopen_recent(app: &AppHandle, entry_id: &str) {
+	let Some(entry) = entry_id
+		.parse::<u64>()
+		.ok()
+		.and_then(|id| app.state::<history::History>().get(id))
+	else {
+		return;
+	};
+	app.state::<clipboard::LastResults>()
+		.set(clipboard::ResultKind::Answer, &entry.answer);
+	crate::show_spotlight_with_answer(app, entry.query, entry.answer);
+}
+
+// The `settings://updated` event this sends also rebuilds the menu
+fn update_setting(app: &AppHandle, values: serde_json::Value) {
+	if let Err(e) = settings::update(app, &values) {
+		log::error!("[tray] {}", e);
+	}
+}
+
+fn open_options(app: &AppHandle) {
+	// Check if options window already exists
+	if let Some(window) = app.get_webview_window("options") {
+		// If it exists, just show and focus it
+		let _ = window.show();
+		let _ = window.set_focus();
+	} else {
+		// Create the options window
+		let options_url = tauri::WebviewUrl::App("index.html?window=options".into());
+		if let Ok(window) = WebviewWindowBuilder::new(app, "options", options_url)
+			.title("Options")
+			.inner_size(560.0, 620.0)
+			.resizable(false)
+			.center()
+			.build()
+		{
+			let _ = window.show();
+			let _ = window.set_focus();
+		}
+	}
+}
@@ -0,0 +1,486 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::{
+	aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+	pbkdf2,
+	rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const VAULT_FILE_NAME: &str = "secrets.vault";
+// Random fallback secret for machines without a readable machine id
+const KEY_FILE_NAME: &str = "vault.key";
+const VAULT_VERSION: u32 = 1;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+// Set before launch to derive the key from a passphrase instead of the machine id
+const PASSPHRASE_ENV: &str = "AI_SPOTLIGHT_VAULT_PASSPHRASE";
+
+// Settings keys whose values live in the vault instead of `settings.json`
+pub const SECRET_KEYS: &[&str] = &["webSearchApiKey"];
+
+// Whether a settings or payload key holds a secret: the vault keys, plus anything named like one,
+// so new fields are redacted without having to be listed
+pub fn is_secret_key(key: &str) -> bool {
+	if SECRET_KEYS.contains(&key) {
+		return true;
+	}
+	let key = key.to_ascii_lowercase();
+	["apikey", "api_key", "token", "secret", "password", "passphrase", "authorization"]
+		.iter()
+		.any(|marker| key.contains(marker))
+}
+
+// A profile's own copy of a secret is stored under `<key>@<profile>`
+pub fn profile_secret_name(key: &str, profile: &str) -> String {
+	format!("{}@{}", key, profile)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SealedSecret {
+	nonce: String,
+	ciphertext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+	version: u32,
+	salt: String,
+	secrets: BTreeMap<String, SealedSecret>,
+}
+
+// Secrets encrypted with AES-256-GCM under a key derived (PBKDF2-SHA256) from a machine-bound secret or a
+// passphrase, so copying the file to another machine doesn't reveal them
+pub struct Vault {
+	path: PathBuf,
+	salt: Vec<u8>,
+	key: LessSafeKey,
+	secrets: Mutex<BTreeMap<String, SealedSecret>>,
+}
+
+impl Vault {
+	pub fn open(data_dir: &Path) -> Result<Self, String> {
+		let path = data_dir.join(VAULT_FILE_NAME);
+		let (salt, secrets) = match std::fs::read(&path) {
+			Ok(bytes) => match parse_vault_file(&bytes) {
+				Ok(Some(existing)) => existing,
+				// Written by another version: leave it alone and run without a vault rather than overwrite it
+				Ok(None) => {
+					return Err(format!(
+						"{} has an unsupported version; secrets are unavailable",
+						path.display()
+					))
+				}
+				// Unreadable as a vault: keep the file for recovery and start over
+				Err(e) => {
+					let backup = path.with_extension("vault.bak");
+					std::fs::rename(&path, &backup)
+						.map_err(|e| format!("Failed to back up corrupt vault: {}", e))?;
+					log::warn!(
+						"[vault] {} is corrupt ({}); moved it to {} and started an empty vault",
+						path.display(),
+						e,
+						backup.display()
+					);
+					(random_bytes(SALT_LEN)?, BTreeMap::new())
+				}
+			},
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+				(random_bytes(SALT_LEN)?, BTreeMap::new())
+			}
+			Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+		};
+
+		let secret = match std::env::var(PASSPHRASE_ENV) {
+			Ok(passphrase) if !passphrase.is_empty() => passphrase.into_bytes(),
+			_ => machine_secret(data_dir)?,
+		};
+		let key = derive_key(&secret, &salt)?;
+
+		Ok(Vault {
+			path,
+			salt,
+			key,
+			secrets: Mutex::new(secrets),
+		})
+	}
+
+	// Decrypt a secret; a key change (other machine, wrong passphrase) reads as unset
+	pub fn get(&self, name: &str) -> Option<String> {
+		let sealed = self
+			.secrets
+			.lock()
+			.expect("vault mutex poisoned")
+			.get(name)
+			.cloned()?;
+		match unseal(&self.key, name, &sealed) {
+			Ok(value) => Some(value),
+			Err(_) => {
+				log::warn!("[vault] Cannot decrypt {}; the vault key changed", name);
+				None
+			}
+		}
+	}
+
+	// All secrets that decrypt, by name
+	pub fn entries(&self) -> BTreeMap<String, String> {
+		let names: Vec<String> = self
+			.secrets
+			.lock()
+			.expect("vault mutex poisoned")
+			.keys()
+			.cloned()
+			.collect();
+		names
+			.into_iter()
+			.filter_map(|name| self.get(&name).map(|value| (name, value)))
+			.collect()
+	}
+
+	// Encrypt and store a secret, or remove it when the value is empty
+	pub fn set(&self, name: &str, value: &str) -> Result<(), String> {
+		let mut secrets = self.secrets.lock().expect("vault mutex poisoned");
+		if value.is_empty() {
+			secrets.remove(name);
+			return self.save(&secrets);
+		}
+		secrets.insert(name.to_string(), seal(&self.key, name, value)?);
+		self.save(&secrets)
+	}
+
+	fn save(&self, secrets: &BTreeMap<String, SealedSecret>) -> Result<(), String> {
+		let file = VaultFile {
+			version: VAULT_VERSION,
+			salt: STANDARD.encode(&self.salt),
+			secrets: secrets.clone(),
+		};
+		let json =
+			serde_json::to_vec_pretty(&file).map_err(|e| format!("Failed to serialize vault: {}", e))?;
+		if let Some(parent) = self.path.parent() {
+			std::fs::create_dir_all(parent)
+				.map_err(|e| format!("Failed to create vault directory: {}", e))?;
+		}
+		write_private(&self.path, &json).map_err(|e| format!("Failed to write vault: {}", e))
+	}
+}
+
+type SealedSecrets = BTreeMap<String, SealedSecret>;
+
+// Salt and secrets of a vault file, or `None` when it has another version
+fn parse_vault_file(bytes: &[u8]) -> Result<Option<(Vec<u8>, SealedSecrets)>, String> {
+	let value = serde_json::from_slice::<serde_json::Value>(bytes).map_err(|e| e.to_string())?;
+	// Checked before the layout, which a newer version may have changed
+	if value.get("version").and_then(|version| version.as_u64()) != Some(VAULT_VERSION as u64) {
+		return Ok(None);
+	}
+	let file = serde_json::from_value::<VaultFile>(value).map_err(|e| e.to_string())?;
+	let salt = STANDARD
+		.decode(&file.salt)
+		.map_err(|e| format!("bad salt: {}", e))?;
+	if salt.len() != SALT_LEN {
+		return Err("bad salt length".to_string());
+	}
+	Ok(Some((salt, file.secrets)))
+}
+
+// Secrets sealed under a passphrase instead of the machine key, for carrying them in a settings export
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PassphraseSealed {
+	salt: String,
+	secrets: BTreeMap<String, SealedSecret>,
+}
+
+impl PassphraseSealed {
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.secrets.keys().map(String::as_str)
+	}
+}
+
+pub fn seal_with_passphrase(
+	passphrase: &str,
+	secrets: &BTreeMap<String, String>,
+) -> Result<PassphraseSealed, String> {
+	if passphrase.is_empty() {
+		return Err("Passphrase must not be empty".to_string());
+	}
+	let salt = random_bytes(SALT_LEN)?;
+	let key = derive_key(passphrase.as_bytes(), &salt)?;
+	let secrets = secrets
+		.iter()
+		.map(|(name, value)| Ok((name.clone(), seal(&key, name, value)?)))
+		.collect::<Result<_, String>>()?;
+	Ok(PassphraseSealed {
+		salt: STANDARD.encode(salt),
+		secrets,
+	})
+}
+
+pub fn open_with_passphrase(
+	passphrase: &str,
+	sealed: &PassphraseSealed,
+) -> Result<BTreeMap<String, String>, String> {
+	let salt = STANDARD
+		.decode(&sealed.salt)
+		.map_err(|e| format!("Corrupt secrets salt: {}", e))?;
+	let key = derive_key(passphrase.as_bytes(), &salt)?;
+	sealed
+		.secrets
+		.iter()
+		.map(|(name, secret)| {
+			let value = unseal(&key, name, secret)
+				.map_err(|_| "Wrong passphrase for the exported secrets".to_string())?;
+			Ok((name.clone(), value))
+		})
+		.collect()
+}
+
+fn derive_key(secret: &[u8], salt: &[u8]) -> Result<LessSafeKey, String> {
+	let mut key_bytes = [0u8; 32];
+	pbkdf2::derive(
+		pbkdf2::PBKDF2_HMAC_SHA256,
+		NonZeroU32::new(PBKDF2_ITERATIONS).expect("non-zero iterations"),
+		salt,
+		secret,
+		&mut key_bytes,
+	);
+	let key = UnboundKey::new(&AES_256_GCM, &key_bytes)
+		.map_err(|_| "Failed to create vault key".to_string())?;
+	Ok(LessSafeKey::new(key))
+}
+
+fn seal(key: &LessSafeKey, name: &str, value: &str) -> Result<SealedSecret, String> {
+	let nonce: [u8; NONCE_LEN] = random_bytes(NONCE_LEN)?
+		.try_into()
+		.map_err(|_| "Failed to generate nonce".to_string())?;
+	let mut in_out = value.as_bytes().to_vec();
+	// The secret's name is authenticated too, so entries can't be swapped in the file
+	key.seal_in_place_append_tag(
+		Nonce::assume_unique_for_key(nonce),
+		Aad::from(name.as_bytes()),
+		&mut in_out,
+	)
+	.map_err(|_| format!("Failed to encrypt {}", name))?;
+	Ok(SealedSecret {
+		nonce: STANDARD.encode(nonce),
+		ciphertext: STANDARD.encode(in_out),
+	})
+}
+
+fn unseal(key: &LessSafeKey, name: &str, sealed: &SealedSecret) -> Result<String, String> {
+	let nonce: [u8; NONCE_LEN] = STANDARD
+		.decode(&sealed.nonce)
+		.ok()
+		.and_then(|nonce| nonce.try_into().ok())
+		.ok_or_else(|| format!("Corrupt nonce for {}", name))?;
+	let mut in_out = STANDARD
+		.decode(&sealed.ciphertext)
+		.map_err(|e| format!("Corrupt ciphertext for {}: {}", name, e))?;
+	let plaintext = key
+		.open_in_place(
+			Nonce::assume_unique_for_key(nonce),
+			Aad::from(name.as_bytes()),
+			&mut in_out,
+		)
+		.map_err(|_| format!("Cannot decrypt {}", name))?;
+	String::from_utf8(plaintext.to_vec()).map_err(|_| format!("Corrupt value for {}", name))
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, String> {
+	let mut bytes = vec![0u8; len];
+	SystemRandom::new()
+		.fill(&mut bytes)
+		.map_err(|_| "Failed to generate random bytes".to_string())?;
+	Ok(bytes)
+}
+
+// Write a file readable only by the current user where the platform supports it. The contents go to a temporary
+// file that is created with those permissions and then renamed over the target, so the file is never readable by
+// others, even briefly, and a crash mid-write leaves the previous version in place.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+	use std::io::Write;
+
+	let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+	temp_name.push(".tmp");
+	let temp_path = path.with_file_name(temp_name);
+	// A leftover from an interrupted write may have other permissions; `mode` only applies to new files
+	match std::fs::remove_file(&temp_path) {
+		Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+		_ => {}
+	}
+
+	let mut options = std::fs::OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::OpenOptionsExt;
+		options.mode(0o600);
+	}
+	let written = options.open(&temp_path).and_then(|mut file| {
+		file.write_all(contents)?;
+		file.sync_all()
+	});
+	if let Err(e) = written.and_then(|()| std::fs::rename(&temp_path, path)) {
+		let _ = std::fs::remove_file(&temp_path);
+		return Err(e);
+	}
+	Ok(())
+}
+
+// A value tied to this machine: the OS machine id, else a random key file created on first use
+fn machine_secret(data_dir: &Path) -> Result<Vec<u8>, String> {
+	if let Some(id) = machine_id() {
+		return Ok(id.into_bytes());
+	}
+
+	let key_path = data_dir.join(KEY_FILE_NAME);
+	if let Ok(bytes) = std::fs::read(&key_path) {
+		if !bytes.is_empty() {
+			return Ok(bytes);
+		}
+	}
+	let bytes = random_bytes(32)?;
+	std::fs::create_dir_all(data_dir)
+		.map_err(|e| format!("Failed to create vault directory: {}", e))?;
+	write_private(&key_path, &bytes).map_err(|e| format!("Failed to write vault key: {}", e))?;
+	Ok(bytes)
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+	["/etc/machine-id", "/var/lib/dbus/machine-id"]
+		.iter()
+		.filter_map(|path| std::fs::read_to_string(path).ok())
+		.map(|id| id.trim().to_string())
+		.find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+	let output = std::process::Command::new("ioreg")
+		.args(["-rd1", "-c", "IOPlatformExpertDevice"])
+		.output()
+		.ok()?;
+	String::from_utf8_lossy(&output.stdout)
+		.lines()
+		.find(|line| line.contains("IOPlatformUUID"))
+		.and_then(|line| line.split('"').nth(3))
+		.map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+	let output = std::process::Command::new("reg")
+		.args([
+			"query",
+			r"HKLM\SOFTWARE\Microsoft\Cryptography",
+			"/v",
+			"MachineGuid",
+		])
+		.output()
+		.ok()?;
+	String::from_utf8_lossy(&output.stdout)
+		.lines()
+		.find(|line| line.contains("MachineGuid"))
+		.and_then(|line| line.split_whitespace().last())
+		.map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn machine_id() -> Option<String> {
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// A fresh directory per test, so tests running in parallel don't share a vault
+	fn test_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("ai-spotlight-vault-{}-{}", name, std::process::id()));
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	#[test]
+	fn round_trips_secrets_across_opens() {
+		let dir = test_dir("round-trip");
+		let vault = Vault::open(&dir).unwrap();
+		vault.set("webSearchApiKey", "key-1").unwrap();
+		vault.set("webSearchApiKey@work", "key-2").unwrap();
+
+		let reopened = Vault::open(&dir).unwrap();
+		assert_eq!(reopened.get("webSearchApiKey").as_deref(), Some("key-1"));
+		assert_eq!(reopened.entries().len(), 2);
+		let contents = std::fs::read_to_string(dir.join(VAULT_FILE_NAME)).unwrap();
+		assert!(!contents.contains("key-1"));
+
+		reopened.set("webSearchApiKey", "").unwrap();
+		assert_eq!(Vault::open(&dir).unwrap().get("webSearchApiKey"), None);
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn swapped_entries_do_not_decrypt() {
+		let dir = test_dir("swapped");
+		let vault = Vault::open(&dir).unwrap();
+		vault.set("a", "first").unwrap();
+		let sealed = vault.secrets.lock().unwrap().get("a").cloned().unwrap();
+		vault.secrets.lock().unwrap().insert("b".to_string(), sealed);
+		assert_eq!(vault.get("b"), None);
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn moves_a_corrupt_vault_aside() {
+		let dir = test_dir("corrupt");
+		let path = dir.join(VAULT_FILE_NAME);
+		std::fs::write(&path, b"not json").unwrap();
+
+		let vault = Vault::open(&dir).unwrap();
+		assert!(vault.entries().is_empty());
+		assert_eq!(std::fs::read(path.with_extension("vault.bak")).unwrap(), b"not json");
+		assert!(!path.exists());
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn leaves_a_newer_vault_alone() {
+		let dir = test_dir("newer");
+		let path = dir.join(VAULT_FILE_NAME);
+		let newer = format!("{{\"version\": {}, \"layout\": \"new\"}}", VAULT_VERSION + 1);
+		std::fs::write(&path, &newer).unwrap();
+
+		assert!(Vault::open(&dir).is_err());
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), newer);
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn writes_files_readable_only_by_the_owner() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let dir = test_dir("private");
+		let path = dir.join("file");
+		std::fs::write(&path, b"old").unwrap();
+		write_private(&path, b"new").unwrap();
+		assert_eq!(std::fs::read(&path).unwrap(), b"new");
+		assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+		assert!(!dir.join("file.tmp").exists());
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn opens_passphrase_sealed_secrets() {
+		let secrets = BTreeMap::from([("webSearchApiKey".to_string(), "key".to_string())]);
+		let sealed = seal_with_passphrase("correct horse", &secrets).unwrap();
+		assert_eq!(sealed.names().collect::<Vec<_>>(), ["webSearchApiKey"]);
+		assert_eq!(open_with_passphrase("correct horse", &sealed).unwrap(), secrets);
+		assert!(open_with_passphrase("wrong", &sealed).is_err());
+		assert!(seal_with_passphrase("", &secrets).is_err());
+	}
+}
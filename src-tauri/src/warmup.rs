@@ -0,0 +1,100 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{ollama, settings};
+
+// Toggling the panel repeatedly within this window doesn't send another preload
+const WARMUP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStatus {
+	pub model: String,
+	// "loading", "ready" or "error"
+	pub status: &'static str,
+	pub error: Option<String>,
+}
+
+// When each model was last warmed up, so panel toggles are throttled per model
+#[derive(Default)]
+pub struct ModelWarmup {
+	last_started: Mutex<HashMap<String, Instant>>,
+}
+
+impl ModelWarmup {
+	// Claim the warm-up for a model, or return false when one ran recently
+	fn claim(&self, model: &str) -> bool {
+		let mut last_started = self.last_started.lock().expect("warmup mutex poisoned");
+		if last_started
+			.get(model)
+			.is_some_and(|started| started.elapsed() < WARMUP_INTERVAL)
+		{
+			return false;
+		}
+		last_started.insert(model.to_string(), Instant::now());
+		true
+	}
+
+	fn release(&self, model: &str) {
+		self.last_started
+			.lock()
+			.expect("warmup mutex poisoned")
+			.remove(model);
+	}
+
+	// Let every model warm up again, e.g. on another server that hasn't loaded it yet
+	pub fn reset(&self) {
+		self.last_started
+			.lock()
+			.expect("warmup mutex poisoned")
+			.clear();
+	}
+}
+
+fn emit_status(app: &AppHandle, model: &str, status: &'static str, error: Option<String>) {
+	let _ = app.emit(
+		"ollama://model-status",
+		ModelStatus {
+			model: model.to_string(),
+			status,
+			error,
+		},
+	);
+}
+
+// Preload the selected model in the background so the first query after idle doesn't pay the load time.
+// Disabled with the `warmUpOnOpen` setting.
+pub fn trigger(app: &AppHandle) {
+	if !settings::read::<bool>(app, "warmUpOnOpen").unwrap_or(true) {
+		return;
+	}
+	let model = settings::Settings::load(app).ollama_model;
+	if model.is_empty() {
+		return;
+	}
+	if !app.state::<ModelWarmup>().claim(&model) {
+		return;
+	}
+
+	let app = app.clone();
+	tauri::async_runtime::spawn(async move {
+		emit_status(&app, &model, "loading", None);
+		let options = ollama::configured_options(&app).non_empty();
+		let keep_alive = ollama::configured_keep_alive(&app);
+		match ollama::preload_model(&model, options, keep_alive).await {
+			Ok(()) => {
+				log::info!("[warmup] Loaded {}", model);
+				emit_status(&app, &model, "ready", None);
+			}
+			Err(e) => {
+				log::warn!("[warmup] Failed to load {}: {}", model, e);
+				// Let the next panel open retry instead of waiting out the interval
+				app.state::<ModelWarmup>().release(&model);
+				emit_status(&app, &model, "error", Some(e));
+			}
+		}
+	});
+}
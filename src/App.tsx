@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow, LogicalSize } from "@tauri-apps/api/window";
 import { writeText } from "@tauri-apps/plugin-clipboard-manager";
 import { Search } from "lucide-react";
@@ -13,20 +14,62 @@ const DROPDOWN_HEIGHT = 60;
 const PADDING = 8; // 2 * p-2 (8px padding)
 const MARGIN = 8; // margin-top of dropdowns
 
+// "all" runs translation and quick answer together; hotkey actions can narrow it to one
+type PanelMode = "all" | "translate" | "ask";
+
+interface PanelQuery {
+	text: string;
+	mode: PanelMode;
+	immediate: boolean;
+	// Set when reopening a past answer from the tray, which is shown instead of asking again
+	answer: string | null;
+	// Text handed over by another app, which waits for Enter instead of running
+	hold: boolean;
+}
+
+interface OllamaHealth {
+	reachable: boolean;
+	version: string | null;
+	loadedModels: string[];
+	error: string | null;
+}
+
+interface ModelStatus {
+	model: string;
+	status: "loading" | "ready" | "error";
+	error: string | null;
+}
+
 function App() {
 	const [query, setQuery] = useState("");
+	const [mode, setMode] = useState<PanelMode>("all");
+	// Queries injected by the backend (clipboard, repeat) run without the typing debounce
+	const [runImmediately, setRunImmediately] = useState(false);
+	const [storedAnswer, setStoredAnswer] = useState<string | null>(null);
+	const [held, setHeld] = useState(false);
 	const [quickAnswerHeight, setQuickAnswerHeight] = useState(0);
+	// Model being preloaded by the backend after the panel opened, shown in the placeholder
+	const [loadingModel, setLoadingModel] = useState<string | null>(null);
+	// Warn in the placeholder before a query fails when Ollama isn't reachable
+	const [ollamaReachable, setOllamaReachable] = useState(true);
 	const inputRef = useRef<HTMLInputElement>(null);
 	const {
 		translation,
 		isLoading: translationLoading,
 		error: translationError,
-	} = useTranslation(query);
+	} = useTranslation(
+		mode === "ask" || held ? "" : query,
+		runImmediately ? 0 : undefined,
+	);
 	const {
-		answer,
+		answer: liveAnswer,
 		isLoading: answerLoading,
 		error: answerError,
-	} = useQuickAnswer(query);
+	} = useQuickAnswer(
+		mode === "translate" || storedAnswer !== null || held ? "" : query,
+		runImmediately ? 0 : undefined,
+	);
+	const answer = storedAnswer ?? liveAnswer;
 
 	// Determine if dropdowns should be visible
 	const showTranslation = translation || translationLoading || translationError;
@@ -58,6 +101,9 @@ function App() {
 		// Hide spotlight window and clear input
 		const hideSpotlight = async () => {
 			setQuery("");
+			setMode("all");
+			setStoredAnswer(null);
+			setHeld(false);
 			// Reset window height
 			await appWindow.setSize(new LogicalSize(680, BASE_HEIGHT + PADDING));
 			await appWindow.hide();
@@ -77,6 +123,56 @@ function App() {
 		};
 	}, []);
 
+	// Hotkey actions from the backend: switch mode, or fill in a query to run right away
+	useEffect(() => {
+		const unlistenMode = listen<PanelMode>("spotlight://mode", (event) => {
+			setMode(event.payload);
+			setRunImmediately(false);
+			setStoredAnswer(null);
+			setHeld(false);
+			setQuery("");
+		});
+		const unlistenQuery = listen<PanelQuery>("spotlight://query", (event) => {
+			setMode(event.payload.mode);
+			setRunImmediately(event.payload.immediate);
+			setStoredAnswer(event.payload.answer);
+			setHeld(event.payload.hold);
+			setQuery(event.payload.text);
+		});
+		// Links and arguments the app was launched with were waiting for this listener
+		void unlistenQuery.then(() => invoke("open_pending_requests"));
+
+		return () => {
+			void unlistenMode.then((unlisten) => unlisten());
+			void unlistenQuery.then((unlisten) => unlisten());
+		};
+	}, []);
+
+	useEffect(() => {
+		invoke<OllamaHealth>("get_ollama_health")
+			.then((health) => setOllamaReachable(health.reachable))
+			.catch((err) => console.error("Failed to check Ollama health:", err));
+		const unlisten = listen<OllamaHealth>("ollama://health", (event) => {
+			setOllamaReachable(event.payload.reachable);
+		});
+
+		return () => {
+			void unlisten.then((unlisten) => unlisten());
+		};
+	}, []);
+
+	useEffect(() => {
+		const unlisten = listen<ModelStatus>("ollama://model-status", (event) => {
+			setLoadingModel(
+				event.payload.status === "loading" ? event.payload.model : null,
+			);
+		});
+
+		return () => {
+			void unlisten.then((unlisten) => unlisten());
+		};
+	}, []);
+
 	// Focus input whenever the component renders (window shown)
 	useEffect(() => {
 		inputRef.current?.focus();
@@ -89,6 +185,25 @@ function App() {
 	const handleInputKeyDown = async (
 		e: React.KeyboardEvent<HTMLInputElement>,
 	) => {
+		// Enter runs text handed over by a link
+		if (e.key === "Enter" && held) {
+			e.preventDefault();
+			setHeld(false);
+			return;
+		}
+
+		// Shift+Enter hands the answer back to the previous app, ready to paste
+		if (e.key === "Enter" && e.shiftKey && answer) {
+			e.preventDefault();
+			try {
+				await invoke("replace_selection", { kind: "answer" });
+				setQuery("");
+			} catch (err) {
+				console.error("Failed to paste back answer:", err);
+			}
+			return;
+		}
+
 		if (e.key === "Enter" && translation) {
 			e.preventDefault();
 			try {
@@ -111,9 +226,20 @@ function App() {
 					ref={inputRef}
 					type="text"
 					className="spotlight-input w-full"
-					placeholder="AI Spotlight"
+					placeholder={
+						!ollamaReachable
+							? "Ollama is not running"
+							: loadingModel
+								? `Loading ${loadingModel}…`
+								: "AI Spotlight"
+					}
 					value={query}
-					onChange={(e) => setQuery(e.target.value)}
+					onChange={(e) => {
+						setRunImmediately(false);
+						setStoredAnswer(null);
+						setHeld(false);
+						setQuery(e.target.value);
+					}}
 					onKeyDown={handleInputKeyDown}
 					autoCorrect="off"
 					autoCapitalize="off"
@@ -1,5 +1,5 @@
-import { Check, Loader2, RefreshCw } from "lucide-react";
-import type { ChangeEvent } from "react";
+import { Check, Loader2, Plus, RefreshCw, X } from "lucide-react";
+import { type ChangeEvent, useEffect, useState } from "react";
 import { Button } from "./components/ui/button";
 import {
 	Combobox,
@@ -22,6 +22,11 @@ import {
 	type TranslationLanguage,
 	translationLanguages,
 } from "./data/translationLanguages";
+import {
+	type HotkeyAction,
+	type HotkeyBinding,
+	useHotkeys,
+} from "./hooks/useHotkeys";
 import { useOllamaModels } from "./hooks/useOllamaModels";
 import { useSettings } from "./hooks/useSettings";
 
@@ -29,6 +34,14 @@ const translationLanguageValues = new Set(
 	translationLanguages.map((language) => language.value),
 );
 
+const hotkeyActions: { value: HotkeyAction; label: string }[] = [
+	{ value: "togglePanel", label: "Toggle panel" },
+	{ value: "translate", label: "Translate" },
+	{ value: "askClipboard", label: "Ask about clipboard" },
+	{ value: "repeatLastQuery", label: "Repeat last query" },
+	{ value: "nextProfile", label: "Next profile" },
+];
+
 const resolveTranslationLanguageValue = (value: unknown): string => {
 	if (!value) {
 		return "";
@@ -44,8 +57,15 @@ const resolveTranslationLanguageValue = (value: unknown): string => {
 };
 
 function Options() {
-	const { settings, updateSetting, isLoading, isSaving, saveSuccess } =
-		useSettings();
+	const {
+		settings,
+		updateSetting,
+		isLoading,
+		isSaving,
+		saveSuccess,
+		saveError,
+		storedSecrets,
+	} = useSettings();
 	const {
 		models,
 		isLoading: modelsLoading,
@@ -54,6 +74,26 @@ function Options() {
 		refetch,
 	} = useOllamaModels();
 
+	const hotkeys = useHotkeys();
+	// Edited locally and saved as a whole, since a half-typed shortcut can't be registered
+	const [hotkeyDraft, setHotkeyDraft] = useState<HotkeyBinding[]>([]);
+
+	useEffect(() => {
+		setHotkeyDraft(hotkeys.bindings);
+	}, [hotkeys.bindings]);
+
+	const updateHotkeyDraft = (index: number, change: Partial<HotkeyBinding>) => {
+		setHotkeyDraft((prev) =>
+			prev.map((binding, i) =>
+				i === index ? { ...binding, ...change } : binding,
+			),
+		);
+	};
+
+	const selectedModel = models.find(
+		(model) => model.name === settings.ollamaModel,
+	);
+
 	const handleModelChange = (value: string) => {
 		// Handle the special "none" value
 		const modelValue = value === "__none__" ? "" : value;
@@ -69,6 +109,10 @@ function Options() {
 		updateSetting("enableThinking", checked);
 	};
 
+	const handleOllamaUrlChange = (event: ChangeEvent<HTMLInputElement>) => {
+		updateSetting("ollamaUrl", event.target.value);
+	};
+
 	const handleWebSearchApiUrlChange = (
 		event: ChangeEvent<HTMLInputElement>,
 	) => {
@@ -81,6 +125,8 @@ function Options() {
 		updateSetting("webSearchApiKey", event.target.value);
 	};
 
+	const hasStoredApiKey = storedSecrets.includes("webSearchApiKey");
+
 	const selectedTranslationLanguage =
 		translationLanguages.find(
 			(language) => language.value === settings.translationSecondLanguage,
@@ -102,6 +148,9 @@ function Options() {
 		updateSetting("translationSecondLanguage", resolvedValue);
 	};
 
+	const hotkeysChanged =
+		JSON.stringify(hotkeyDraft) !== JSON.stringify(hotkeys.bindings);
+
 	if (isLoading) {
 		return (
 			<main className="options-window">
@@ -131,6 +180,14 @@ function Options() {
 					<span>Settings saved</span>
 				</div>
 
+				{/* Hotkeys Saved Toast */}
+				<div
+					className={`options-toast ${hotkeys.saveSuccess ? "options-toast-visible" : ""}`}
+				>
+					<Check className="h-4 w-4 text-green-500" />
+					<span>Hotkeys saved</span>
+				</div>
+
 				{/* Models Reloaded Toast */}
 				<div
 					className={`options-toast ${modelsRefreshSuccess ? "options-toast-visible" : ""}`}
@@ -141,9 +198,28 @@ function Options() {
 
 				<h1 className="options-title">Options</h1>
 
+				{saveError && (
+					<p className="options-warning">Settings not saved: {saveError}</p>
+				)}
+
 				<div className="options-section">
 					<h2 className="options-section-title">AI Model</h2>
 
+					<div className="options-field">
+						<Label htmlFor="ollama-url">Ollama URL</Label>
+						<input
+							id="ollama-url"
+							type="url"
+							className="options-input"
+							placeholder="http://127.0.0.1:11434"
+							value={settings.ollamaUrl}
+							onChange={handleOllamaUrlChange}
+						/>
+						<p className="options-hint">
+							Leave empty to use the local Ollama server.
+						</p>
+					</div>
+
 					<div className="options-field">
 						<Label htmlFor="model-select">Ollama Model</Label>
 						<div className="options-field-row">
@@ -162,8 +238,8 @@ function Options() {
 										</span>
 									</SelectItem>
 									{models.map((model) => (
-										<SelectItem key={model} value={model}>
-											{model}
+										<SelectItem key={model.name} value={model.name}>
+											{model.name}
 										</SelectItem>
 									))}
 								</SelectContent>
@@ -200,6 +276,13 @@ function Options() {
 								Please add a model to Ollama to enable AI responses.
 							</p>
 						)}
+
+						{selectedModel?.supports_tools === false && (
+							<p className="options-warning">
+								This model does not support tool calling, so quick answers
+								won't use web search.
+							</p>
+						)}
 					</div>
 
 					<div className="options-field options-field-toggle">
@@ -242,16 +325,31 @@ function Options() {
 
 					<div className="options-field">
 						<Label htmlFor="web-search-key">Search API Key</Label>
-						<input
-							id="web-search-key"
-							type="password"
-							className="options-input"
-							placeholder="Enter API key"
-							autoComplete="new-password"
-							value={settings.webSearchApiKey}
-							onChange={handleWebSearchApiKeyChange}
-						/>
-						<p className="options-hint">Stored locally in app settings.</p>
+						<div className="options-field-row">
+							<input
+								id="web-search-key"
+								type="password"
+								className="options-input"
+								placeholder={
+									hasStoredApiKey ? "Saved (type to replace)" : "Enter API key"
+								}
+								autoComplete="new-password"
+								value={settings.webSearchApiKey}
+								onChange={handleWebSearchApiKeyChange}
+							/>
+							{hasStoredApiKey && (
+								<Button
+									variant="outline"
+									onClick={() => updateSetting("webSearchApiKey", "")}
+									title="Remove the saved API key"
+								>
+									Clear
+								</Button>
+							)}
+						</div>
+						<p className="options-hint">
+							Encrypted in a local vault and never shown again after saving.
+						</p>
 					</div>
 				</div>
 
@@ -301,6 +399,90 @@ function Options() {
 						</p>
 					</div>
 				</div>
+
+				<div className="options-section">
+					<h2 className="options-section-title">Hotkeys</h2>
+					<p className="options-hint options-section-hint">
+						Global shortcuts, e.g. Ctrl+Shift+Space or Alt+Space. They are
+						registered as soon as you save, without a restart.
+					</p>
+
+					{hotkeyDraft.map((binding, index) => (
+						<div
+							// biome-ignore lint/suspicious/noArrayIndexKey: rows have no identity besides their position
+							key={index}
+							className="options-field options-field-row"
+						>
+							<input
+								aria-label="Shortcut"
+								className="options-input"
+								placeholder="Ctrl+Shift+Space"
+								value={binding.shortcut}
+								onChange={(event) =>
+									updateHotkeyDraft(index, { shortcut: event.target.value })
+								}
+							/>
+							<Select
+								value={binding.action}
+								onValueChange={(value) =>
+									updateHotkeyDraft(index, { action: value as HotkeyAction })
+								}
+							>
+								<SelectTrigger aria-label="Action" className="options-select">
+									<SelectValue />
+								</SelectTrigger>
+								<SelectContent>
+									{hotkeyActions.map((action) => (
+										<SelectItem key={action.value} value={action.value}>
+											{action.label}
+										</SelectItem>
+									))}
+								</SelectContent>
+							</Select>
+							<Button
+								variant="outline"
+								size="icon"
+								onClick={() =>
+									setHotkeyDraft((prev) => prev.filter((_, i) => i !== index))
+								}
+								title="Remove this hotkey"
+							>
+								<X className="h-4 w-4" />
+							</Button>
+						</div>
+					))}
+
+					<div className="options-field-row">
+						<Button
+							variant="outline"
+							onClick={() =>
+								setHotkeyDraft((prev) => [
+									...prev,
+									{ shortcut: "", action: "togglePanel" },
+								])
+							}
+						>
+							<Plus className="h-4 w-4" />
+							Add hotkey
+						</Button>
+						<Button
+							onClick={() => hotkeys.save(hotkeyDraft)}
+							disabled={!hotkeysChanged || hotkeys.isSaving}
+						>
+							{hotkeys.isSaving && <Loader2 className="h-4 w-4 animate-spin" />}
+							Save hotkeys
+						</Button>
+					</div>
+
+					{hotkeys.error && (
+						<p className="options-error">Hotkeys not saved: {hotkeys.error}</p>
+					)}
+					{hotkeyDraft.length === 0 && (
+						<p className="options-hint">
+							Without hotkeys, the default toggle shortcut is used.
+						</p>
+					)}
+				</div>
 			</div>
 		</main>
 	);
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useCallback, useEffect, useState } from "react";
+
+export type HotkeyAction =
+	| "togglePanel"
+	| "translate"
+	| "askClipboard"
+	| "repeatLastQuery"
+	| "nextProfile";
+
+export interface HotkeyBinding {
+	shortcut: string;
+	action: HotkeyAction;
+}
+
+interface UseHotkeysReturn {
+	bindings: HotkeyBinding[];
+	isLoading: boolean;
+	isSaving: boolean;
+	saveSuccess: boolean;
+	error: string | null;
+	save: (bindings: HotkeyBinding[]) => Promise<void>;
+}
+
+// Hotkey bindings as the backend has them. Saving registers the new shortcuts before storing them; if the OS refuses
+// one, nothing is saved and the previous shortcuts stay active.
+export function useHotkeys(): UseHotkeysReturn {
+	const [bindings, setBindings] = useState<HotkeyBinding[]>([]);
+	const [isLoading, setIsLoading] = useState(true);
+	const [isSaving, setIsSaving] = useState(false);
+	const [saveSuccess, setSaveSuccess] = useState(false);
+	const [error, setError] = useState<string | null>(null);
+
+	useEffect(() => {
+		const load = async () => {
+			try {
+				setBindings(await invoke<HotkeyBinding[]>("get_hotkeys"));
+			} catch (err) {
+				console.error("Failed to load hotkeys:", err);
+				setError(err instanceof Error ? err.message : String(err));
+			} finally {
+				setIsLoading(false);
+			}
+		};
+
+		load();
+	}, []);
+
+	const save = useCallback(async (next: HotkeyBinding[]) => {
+		setIsSaving(true);
+		setSaveSuccess(false);
+		setError(null);
+
+		try {
+			await invoke("update_hotkeys", { bindings: next });
+			setBindings(next);
+			setSaveSuccess(true);
+			setTimeout(() => setSaveSuccess(false), 2000);
+		} catch (err) {
+			console.error("Failed to save hotkeys:", err);
+			setError(err instanceof Error ? err.message : String(err));
+		} finally {
+			setIsSaving(false);
+		}
+	}, []);
+
+	return { bindings, isLoading, isSaving, saveSuccess, error, save };
+}
@@ -1,8 +1,23 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useRef, useState } from "react";
 
+export interface OllamaModel {
+	name: string;
+	size: number;
+	modified_at: string;
+	digest: string;
+	details: {
+		family: string;
+		parameter_size: string;
+		quantization_level: string;
+	};
+	supports_tools: boolean | null;
+	supports_thinking: boolean | null;
+	context_length: number | null;
+}
+
 interface UseOllamaModelsReturn {
-	models: string[];
+	models: OllamaModel[];
 	isLoading: boolean;
 	error: string | null;
 	refreshSuccess: boolean;
@@ -10,7 +25,7 @@ interface UseOllamaModelsReturn {
 }
 
 export function useOllamaModels(): UseOllamaModelsReturn {
-	const [models, setModels] = useState<string[]>([]);
+	const [models, setModels] = useState<OllamaModel[]>([]);
 	const [isLoading, setIsLoading] = useState(true);
 	const [error, setError] = useState<string | null>(null);
 	const [refreshSuccess, setRefreshSuccess] = useState(false);
@@ -21,7 +36,9 @@ export function useOllamaModels(): UseOllamaModelsReturn {
 		setError(null);
 
 		try {
-			const result = await invoke<string[]>("list_models");
+			const result = await invoke<OllamaModel[]>("list_models", {
+				includeCapabilities: true,
+			});
 			setModels(result);
 			// Show success toast only on manual refresh, not initial load
 			if (!isInitialLoadRef.current) {
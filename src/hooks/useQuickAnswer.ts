@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useRef, useState } from "react";
-import { getSettingsSnapshot, useSettingsReader } from "./useSettings";
+import { useSettingsReader } from "./useSettings";
 
 interface UseQuickAnswerReturn {
 	answer: string | null;
@@ -16,27 +16,13 @@ export function useQuickAnswer(
 	const [isLoading, setIsLoading] = useState(false);
 	const [error, setError] = useState<string | null>(null);
 	const abortRef = useRef(false);
-	const {
-		ollamaModel,
-		enableThinking,
-		webSearchApiUrl,
-		webSearchApiKey,
-		isLoading: settingsLoading,
-	} = useSettingsReader();
+	const { ollamaModel, isLoading: settingsLoading } = useSettingsReader();
 
 	const getQuickAnswer = useCallback(
-		async (
-			inputText: string,
-			model: string,
-			think: boolean,
-			searchApiUrl: string,
-			searchApiKey: string,
-		) => {
+		async (inputText: string, model: string) => {
 			console.log("[useQuickAnswer] getQuickAnswer called with:", {
 				model,
-				think,
 				textLength: inputText.length,
-				hasWebSearchApiKey: Boolean(searchApiKey),
 			});
 
 			// Skip if no model is configured
@@ -61,24 +47,9 @@ export function useQuickAnswer(
 			setError(null);
 
 			try {
-				const latestSettings = await getSettingsSnapshot();
-				const effectiveModel = latestSettings.ollamaModel || model;
-				const effectiveThinking = latestSettings.enableThinking;
-				const effectiveSearchApiUrl =
-					latestSettings.webSearchApiUrl || searchApiUrl;
-				const effectiveSearchApiKey =
-					latestSettings.webSearchApiKey || searchApiKey;
-
-				console.log(
-					"[useQuickAnswer] Invoking quick_answer with enableThinking:",
-					effectiveThinking,
-				);
+				// The backend reads the model, thinking and web search settings itself
 				const result = await invoke<string>("quick_answer", {
 					text: inputText,
-					model: effectiveModel,
-					enableThinking: effectiveThinking,
-					webSearchApiUrl: effectiveSearchApiUrl,
-					webSearchApiKey: effectiveSearchApiKey,
 				});
 
 				if (abortRef.current) return;
@@ -121,13 +92,7 @@ export function useQuickAnswer(
 		}
 
 		const timeoutId = setTimeout(() => {
-			getQuickAnswer(
-				text,
-				ollamaModel,
-				enableThinking,
-				webSearchApiUrl,
-				webSearchApiKey,
-			);
+			getQuickAnswer(text, ollamaModel);
 		}, debounceMs);
 
 		return () => {
@@ -138,16 +103,7 @@ export function useQuickAnswer(
 			setError(null);
 			void invoke("cancel_quick_answer").catch(() => {});
 		};
-	}, [
-		text,
-		debounceMs,
-		getQuickAnswer,
-		ollamaModel,
-		enableThinking,
-		webSearchApiUrl,
-		webSearchApiKey,
-		settingsLoading,
-	]);
+	}, [text, debounceMs, getQuickAnswer, ollamaModel, settingsLoading]);
 
 	return { answer, isLoading, error };
 }
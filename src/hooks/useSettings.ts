@@ -1,9 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
-import { emit, emitTo } from "@tauri-apps/api/event";
-import { LazyStore } from "@tauri-apps/plugin-store";
 import { useCallback, useEffect, useRef, useState } from "react";
 
 export interface Settings {
+	ollamaUrl: string;
 	ollamaModel: string;
 	enableThinking: boolean;
 	webSearchApiUrl: string;
@@ -12,6 +11,7 @@ export interface Settings {
 }
 
 const DEFAULT_SETTINGS: Settings = {
+	ollamaUrl: "",
 	ollamaModel: "",
 	enableThinking: true,
 	webSearchApiUrl: "",
@@ -19,43 +19,31 @@ const DEFAULT_SETTINGS: Settings = {
 	translationSecondLanguage: "",
 };
 
-const STORE_PATH = "settings.json";
 const SAVE_DEBOUNCE_MS = 250;
-const SENSITIVE_SETTING_KEYS = new Set<keyof Settings>(["webSearchApiKey"]);
 
-// Singleton store instance
-let storeInstance: LazyStore | null = null;
-
-function getStore(): LazyStore {
-	if (!storeInstance) {
-		storeInstance = new LazyStore(STORE_PATH);
-	}
-	return storeInstance;
+// Secret settings come back blank; `storedSecrets` names the ones saved in the backend vault
+interface SettingsView extends Settings {
+	storedSecrets: string[];
 }
 
-export async function getSettingsSnapshot(): Promise<Settings> {
-	const store = getStore();
-	await store.init();
-	await store.reload();
-
-	const storedModel = await store.get<string>("ollamaModel");
-	const storedEnableThinking = await store.get<boolean>("enableThinking");
-	const storedWebSearchApiUrl = await store.get<string>("webSearchApiUrl");
-	const storedWebSearchApiKey = await store.get<string>("webSearchApiKey");
-	const storedTranslationSecondLanguage = await store.get<string>(
-		"translationSecondLanguage",
-	);
+// Settings as validated by the backend; on first run, pick and save the first available Ollama model
+async function loadSettings(): Promise<SettingsView> {
+	const settings = await invoke<SettingsView>("get_settings");
+	if (settings.ollamaModel) {
+		return settings;
+	}
 
-	return {
-		...DEFAULT_SETTINGS,
-		ollamaModel: storedModel ?? DEFAULT_SETTINGS.ollamaModel,
-		enableThinking: storedEnableThinking ?? DEFAULT_SETTINGS.enableThinking,
-		webSearchApiUrl: storedWebSearchApiUrl ?? DEFAULT_SETTINGS.webSearchApiUrl,
-		webSearchApiKey: storedWebSearchApiKey ?? DEFAULT_SETTINGS.webSearchApiKey,
-		translationSecondLanguage:
-			storedTranslationSecondLanguage ??
-			DEFAULT_SETTINGS.translationSecondLanguage,
-	};
+	try {
+		const models = await invoke<{ name: string }[]>("list_models");
+		if (models.length > 0) {
+			return await invoke<SettingsView>("update_settings", {
+				values: { ollamaModel: models[0].name },
+			});
+		}
+	} catch {
+		// Ollama not available, keep empty model
+	}
+	return settings;
 }
 
 interface UseSettingsReturn {
@@ -64,6 +52,8 @@ interface UseSettingsReturn {
 	isLoading: boolean;
 	isSaving: boolean;
 	saveSuccess: boolean;
+	saveError: string | null;
+	storedSecrets: string[];
 }
 
 export function useSettings(): UseSettingsReturn {
@@ -71,64 +61,19 @@ export function useSettings(): UseSettingsReturn {
 	const [isLoading, setIsLoading] = useState(true);
 	const [isSaving, setIsSaving] = useState(false);
 	const [saveSuccess, setSaveSuccess] = useState(false);
+	const [saveError, setSaveError] = useState<string | null>(null);
+	const [storedSecrets, setStoredSecrets] = useState<string[]>([]);
 	const saveTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 	const pendingChangesRef = useRef<Partial<Settings>>({});
 	const initializedRef = useRef(false);
 
-	// Load settings from store
+	// Load settings from the backend
 	useEffect(() => {
-		const loadSettings = async () => {
-			const store = getStore();
-
+		const load = async () => {
 			try {
-				const storedModel = await store.get<string>("ollamaModel");
-				const storedEnableThinking = await store.get<boolean>("enableThinking");
-				const storedWebSearchApiUrl =
-					await store.get<string>("webSearchApiUrl");
-				const storedWebSearchApiKey =
-					await store.get<string>("webSearchApiKey");
-				const storedTranslationSecondLanguage = await store.get<string>(
-					"translationSecondLanguage",
-				);
-
-				const newSettings: Settings = { ...DEFAULT_SETTINGS };
-
-				// Load enableThinking (default to true if not set)
-				newSettings.enableThinking = storedEnableThinking ?? true;
-				newSettings.webSearchApiUrl =
-					typeof storedWebSearchApiUrl === "string"
-						? storedWebSearchApiUrl
-						: "";
-				newSettings.webSearchApiKey =
-					typeof storedWebSearchApiKey === "string"
-						? storedWebSearchApiKey
-						: "";
-				newSettings.translationSecondLanguage =
-					typeof storedTranslationSecondLanguage === "string"
-						? storedTranslationSecondLanguage
-						: "";
-
-				// If we have a stored model that's not empty, use it
-				if (storedModel) {
-					newSettings.ollamaModel = storedModel;
-				} else {
-					// First run OR empty model: try to get first available model from Ollama
-					try {
-						const models = await invoke<string[]>("list_models");
-						const firstModel = models.length > 0 ? models[0] : "";
-						newSettings.ollamaModel = firstModel;
-						// Save the initial setting
-						await store.set("ollamaModel", firstModel);
-						await store.save();
-					} catch {
-						// Ollama not available, keep empty model
-						newSettings.ollamaModel = "";
-						await store.set("ollamaModel", "");
-						await store.save();
-					}
-				}
-
-				setSettings(newSettings);
+				const { storedSecrets, ...loaded } = await loadSettings();
+				setSettings(loaded);
+				setStoredSecrets(storedSecrets);
 			} catch (err) {
 				console.error("Failed to load settings:", err);
 			} finally {
@@ -137,14 +82,14 @@ export function useSettings(): UseSettingsReturn {
 			}
 		};
 
-		loadSettings();
+		load();
 	}, []);
 
-	// Save settings to store with debounce
+	// Save settings through the backend with debounce
 	const saveSettings = useCallback(async (newSettings: Partial<Settings>) => {
-		const store = getStore();
 		setIsSaving(true);
 		setSaveSuccess(false);
+		setSaveError(null);
 
 		console.log("[useSettings] saveSettings called with:", {
 			...newSettings,
@@ -154,33 +99,18 @@ export function useSettings(): UseSettingsReturn {
 		});
 
 		try {
-			for (const [key, value] of Object.entries(newSettings)) {
-				const settingKey = key as keyof Settings;
-				const loggedValue = SENSITIVE_SETTING_KEYS.has(settingKey)
-					? value
-						? "[redacted]"
-						: value
-					: value;
-				console.log("[useSettings] Saving to store:", key, "=", loggedValue);
-				await store.set(key, value);
-			}
-			await store.save();
-			console.log("[useSettings] Store saved successfully");
+			// The backend validates, saves and emits `settings://updated` for the changed keys
+			const view = await invoke<SettingsView>("update_settings", {
+				values: newSettings,
+			});
+			setStoredSecrets(view.storedSecrets);
+			console.log("[useSettings] Settings saved successfully");
 			setSaveSuccess(true);
 			// Reset success indicator after 2 seconds
 			setTimeout(() => setSaveSuccess(false), 2000);
-			const payload = {
-				keys: Object.keys(newSettings),
-			};
-			try {
-				await invoke("log_settings_update", { values: newSettings });
-				await emit("settings://updated", payload);
-				await emitTo("spotlight", "settings://updated", payload);
-			} catch (err) {
-				console.error("[useSettings] Post-save notifications failed:", err);
-			}
 		} catch (err) {
 			console.error("Failed to save settings:", err);
+			setSaveError(err instanceof Error ? err.message : String(err));
 		} finally {
 			setIsSaving(false);
 		}
@@ -218,7 +148,15 @@ export function useSettings(): UseSettingsReturn {
 		};
 	}, []);
 
-	return { settings, updateSetting, isLoading, isSaving, saveSuccess };
+	return {
+		settings,
+		updateSetting,
+		isLoading,
+		isSaving,
+		saveSuccess,
+		saveError,
+		storedSecrets,
+	};
 }
 
 // Hook for reading settings only (for use in other components like useQuickAnswer)
@@ -247,78 +185,17 @@ export function useSettingsReader(): {
 	const [isLoading, setIsLoading] = useState(true);
 
 	useEffect(() => {
-		const loadSettings = async () => {
-			const store = getStore();
+		const load = async () => {
 			try {
-				const storedModel = await store.get<string>("ollamaModel");
-				const storedEnableThinking = await store.get<boolean>("enableThinking");
-				const storedWebSearchApiUrl =
-					await store.get<string>("webSearchApiUrl");
-				const storedWebSearchApiKey =
-					await store.get<string>("webSearchApiKey");
-				const storedTranslationSecondLanguage = await store.get<string>(
-					"translationSecondLanguage",
-				);
-
-				console.log("[useSettingsReader] Loaded from store:", {
-					storedModel,
-					storedEnableThinking,
-					storedWebSearchApiUrl,
-					hasWebSearchApiKey: Boolean(storedWebSearchApiKey),
-				});
-
-				let ollamaModel = "";
-				const enableThinking = storedEnableThinking ?? true;
-				const webSearchApiUrl =
-					typeof storedWebSearchApiUrl === "string"
-						? storedWebSearchApiUrl
-						: "";
-				const webSearchApiKey =
-					typeof storedWebSearchApiKey === "string"
-						? storedWebSearchApiKey
-						: "";
-				const translationSecondLanguage =
-					typeof storedTranslationSecondLanguage === "string"
-						? storedTranslationSecondLanguage
-						: "";
-
-				console.log(
-					"[useSettingsReader] enableThinking resolved to:",
-					enableThinking,
-				);
-
-				// If we have a stored model that's not empty, use it
-				if (storedModel) {
-					ollamaModel = storedModel;
-				} else {
-					// First run OR empty model: try to get first available model from Ollama
-					try {
-						const models = await invoke<string[]>("list_models");
-						const firstModel = models.length > 0 ? models[0] : "";
-						ollamaModel = firstModel;
-						// Save the initial setting
-						await store.set("ollamaModel", firstModel);
-						await store.save();
-					} catch {
-						// Ollama not available, keep empty model
-						ollamaModel = "";
-					}
-				}
-
-				console.log("[useSettingsReader] Final settings:", {
-					ollamaModel,
-					enableThinking,
-					webSearchApiUrl,
-					hasWebSearchApiKey: Boolean(webSearchApiKey),
-					translationSecondLanguage,
-				});
-				setSettings({
-					ollamaModel,
-					enableThinking,
-					webSearchApiUrl,
-					webSearchApiKey,
-					translationSecondLanguage,
+				const loaded = await loadSettings();
+				console.log("[useSettingsReader] Loaded settings:", {
+					ollamaModel: loaded.ollamaModel,
+					enableThinking: loaded.enableThinking,
+					webSearchApiUrl: loaded.webSearchApiUrl,
+					hasWebSearchApiKey: loaded.storedSecrets.includes("webSearchApiKey"),
+					translationSecondLanguage: loaded.translationSecondLanguage,
 				});
+				setSettings(loaded);
 			} catch (err) {
 				console.error("Failed to load settings:", err);
 				setSettings({
@@ -333,7 +210,7 @@ export function useSettingsReader(): {
 			}
 		};
 
-		loadSettings();
+		load();
 	}, []);
 
 	return { ...settings, isLoading };
@@ -1,6 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useRef, useState } from "react";
-import { useSettingsReader } from "./useSettings";
 
 interface TranslationResult {
 	text: string;
@@ -28,11 +27,9 @@ export function useTranslation(
 	const [isLoading, setIsLoading] = useState(false);
 	const [error, setError] = useState<string | null>(null);
 	const abortRef = useRef(false);
-	const { translationSecondLanguage, isLoading: settingsLoading } =
-		useSettingsReader();
 
 	const translateText = useCallback(
-		async (inputText: string, targetLanguage: string) => {
+		async (inputText: string) => {
 			// Skip empty text
 			if (!inputText.trim()) {
 				setTranslation(null);
@@ -46,9 +43,9 @@ export function useTranslation(
 			setError(null);
 
 			try {
+				// The backend reads the second language from settings
 				const result = await invoke<RustTranslationResult>("translate_text", {
 					text: inputText,
-					targetLanguage,
 				});
 
 				if (abortRef.current) return;
@@ -87,12 +84,8 @@ export function useTranslation(
 	);
 
 	useEffect(() => {
-		if (settingsLoading) {
-			return;
-		}
-
 		const timeoutId = setTimeout(() => {
-			translateText(text, translationSecondLanguage);
+			translateText(text);
 		}, debounceMs);
 
 		return () => {
@@ -103,13 +96,7 @@ export function useTranslation(
 			setError(null);
 			void invoke("cancel_translate_text").catch(() => {});
 		};
-	}, [
-		text,
-		debounceMs,
-		translateText,
-		translationSecondLanguage,
-		settingsLoading,
-	]);
+	}, [text, debounceMs, translateText]);
 
 	return { translation, isLoading, error };
 }